@@ -2,7 +2,6 @@ class test2 {
     constructor() {
         this.changed = false;
         this.last = Time.elapsed();
-        this.last_dest = 1.0;
         this.mesh = null;
         this.texture = null;
         this.some = new Math.Vector3(3.0, 3.0, -5.0);
@@ -11,10 +10,43 @@ class test2 {
         this.cameraRot = Math.quaternionFromEulerAngles(new Math.Vector3(-0.55, 0.2, 0.0));
     }
 
+    start() {
+        setInterval(() => {
+            if (!this.create) {
+                return;
+            }
+            console.log("creating new cow");
+            this.last = Time.elapsed();
+            let n = World.createGameObject();
+
+            World.spawn({
+                name: "heh2",
+                position: new Math.Vector3(this.last, Math.Sin(this.last), 5.0),
+                mesh: "cow1",
+                texture: "tex1"
+            });
+        }, 1.0);
+        this.destroyCows();
+    }
+
+    async destroyCows() {
+        await wait(1.0);
+        while (true) {
+            await wait(2.0);
+            if (this.create) {
+                console.log("destroying");
+                let n = World.findByName("heh2");
+                if (n.length > 0) {
+                    n[0].destroy();
+                }
+            }
+        }
+    }
+
     update() {
         let posit = this.go.position;
         this.some.x = 3.0 * Math.Sin(Time.elapsed());
-        World.setCameraPosition(this.some); // to jeszcze nie działa :)
+        World.setCameraPosition(this.some);
         World.setCameraRotation(this.cameraRot);
 
         if (Input.isMouseKeyPressed(0)) {
@@ -27,36 +59,19 @@ class test2 {
         pos.x = 12.0;
         let pos2 = this.go.globalPosition;
 
-        // if (!this.mesh) {
-        //     this.mesh = Resources.getMesh("cow1");
-        // }
+        if (!this.mesh) {
+            this.mesh = Resources.getMesh("cow1");
+        }
 
-        // if (!this.texture) {
-        //     this.texture = Resources.getTexture("tex1.png");
-        // }
+        if (!this.texture) {
+            this.texture = Resources.getTexture("tex1");
+        }
         if (Input.keyPressedInLastFrame("A")) {
             console.log("changing state :)")
             this.create = !this.create;
         }
-        if (this.create && Time.elapsed() > this.last + 1.0) {
-            console.log("creating new cow");
-            this.last = Time.elapsed();
-            let n = World.createGameObject();
-
-            World.aniasFunction({
-                name: "heh2",
-                position: new Math.Vector3(this.last, Math.Sin(this.last), 5.0),
-                mesh: "cow1",
-                texture: "tex1"
-            });
-        }
-        if (this.create && Time.elapsed() > this.last_dest + 2.0) {
-            this.last_dest = Time.elapsed();
-            console.log("destroying");
-            let n = World.findByName("heh2");
-            if (n.length > 0) {
-                n[0].destroy();
-            }
+        if (Input.keyPressedInLastFrame("K")) {
+            Events.publish("player_died", { cause: "pressed K" });
         }
     }
 }
@@ -64,7 +79,9 @@ class test2 {
 
 console.log("initializing world");
 
-let floor = World.aniasFunction({
+Events.on("level_loaded", e => console.log("level loaded: ", e.scene));
+
+let floor = World.spawn({
     name: "floor",
     mesh: "floor",
     texture: "creature",
@@ -73,7 +90,7 @@ let floor = World.aniasFunction({
 });
 
 let position = new Math.Vector3(10, 20, 30);
-let go2 = World.aniasFunction({
+let go2 = World.spawn({
     name: "heheszko", position: position, controller: new test2()
 });
 
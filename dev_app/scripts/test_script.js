@@ -2,7 +2,18 @@ test_script = class {
     constructor() {
     }
 
-    update() {
+    start() {
+        console.log("test_script started on ", this.go.name);
+    }
+
+    update(dt) {
         // lol();
     }
+
+    onParentChanged(newParent, oldParent) {
+    }
+
+    onDestroy() {
+        console.log("test_script destroyed on ", this.go.name);
+    }
 }
@@ -1,14 +1,27 @@
 use game_object::GameObject;
 use hierarchy::TransformHierarchy;
 use just_core::ecs::prelude::*;
-use just_core::math::{Quat, Vec2, Vec3};
+use just_core::events::{Event, EventBus, EventReader};
+use just_core::math::{look_rotation, Quat, Vec2, Vec3};
 use just_core::{game_object, hierarchy};
 use just_input::{InputChannel, InputEvent, InputReader, KeyCode, KeyboardState, MouseState};
 use just_wgpu::{RenderingSystem, ScreenData, Ui};
+use serde::{Deserialize, Serialize};
 use std::f32::consts::PI;
 
+/// Published by scripts, player is moved back to the start.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlayerDied {
+    pub cause: String,
+}
+
+impl Event for PlayerDied {
+    const NAME: &'static str = "player_died";
+}
+
 struct GameState {
     input_reader: InputReader,
+    player_died_reader: EventReader,
     player: Entity,
     camera_lookat: Entity,
     pan: Option<PanningAction>,
@@ -39,19 +52,24 @@ impl GameLogic {
             let mut channel = world.resources.get_mut::<InputChannel>().unwrap();
             channel.register_reader()
         };
+        let player_died_reader = {
+            let mut bus = world.resources.get_mut::<EventBus>().unwrap();
+            bus.declare_type::<PlayerDied>();
+            bus.register_reader(PlayerDied::NAME).unwrap()
+        };
 
         let id = GameObject::create_empty(world);
-        GameObject::set_name(world, id, "duh".to_owned());
+        GameObject::set_name(world, id, "duh".to_owned()).unwrap();
         //TransformHierarchy::set_local_position(&mut self.world, id, Vec3::new(10.0, 20.0, 30.0));
         RenderingSystem::add_renderable(world, id, "cow1", "creature");
 
         let camera_lookat = GameObject::create_empty(world);
 
         let id2 = GameObject::create_empty(world);
-        GameObject::set_name(world, id2, "duhesse".to_owned());
+        GameObject::set_name(world, id2, "duhesse".to_owned()).unwrap();
         RenderingSystem::add_renderable(world, id2, "floor", "grassland");
-        TransformHierarchy::set_local_position(world, id2, Vec3::new(-20.0, -2.0, 20.0));
-        TransformHierarchy::set_local_rotation(world, id2, Quat::from_rotation_y(-PI / 4.0));
+        TransformHierarchy::set_local_position(world, id2, Vec3::new(-20.0, -2.0, 20.0)).unwrap();
+        TransformHierarchy::set_local_rotation(world, id2, Quat::from_rotation_y(-PI / 4.0)).unwrap();
         //TransformHierarchy::set_local_scale(world, id2, Vec3::new(10.0, 10.0, 10.0));
 
         {
@@ -61,6 +79,7 @@ impl GameLogic {
         }
         world.resources.insert(GameState {
             input_reader: reader,
+            player_died_reader,
             player: id,
             camera_lookat,
             pan: None,
@@ -120,6 +139,7 @@ impl GameLogic {
         };
 
         Self::handle_player_input(world, player_input);
+        Self::handle_player_deaths(world);
 
         {
             let (player, camera) = {
@@ -127,31 +147,59 @@ impl GameLogic {
                 (state.player, state.camera_lookat)
             };
 
-            let lookat = TransformHierarchy::get_local_position(world, camera);
-            let player = TransformHierarchy::get_local_position(world, player);
+            // player can be destroyed by scripts
+            let (lookat, player) = match (
+                TransformHierarchy::get_local_position(world, camera),
+                TransformHierarchy::get_local_position(world, player),
+            ) {
+                (Ok(lookat), Ok(player)) => (lookat, player),
+                _ => return,
+            };
             let new_lookat = lookat * 0.91 + player * 0.09;
-            TransformHierarchy::set_local_position(world, camera, new_lookat);
+            TransformHierarchy::set_local_position(world, camera, new_lookat).unwrap();
             {
                 let mut screen_data = world.resources.get_mut::<just_wgpu::ScreenData>().unwrap();
                 screen_data.camera.position =
                     new_lookat + Vec3::new(10.0, 10.0 * 2.0f32.sqrt() * 4.0 / 3.0, -10.0) * 0.5;
                 // camera_data.position = new_lookat + Vec3::new(0.0, 1.0, 2.0);
-                let cam_rot = just_core::glam::Mat4::look_at_lh(
-                    screen_data.camera.position,
-                    new_lookat,
-                    Vec3::new(0.0, 1.0, 0.0),
-                );
-                screen_data.camera.rotation = Quat::from_mat4(&cam_rot);
+                // camera keeps rotation of its view, which is inverse of its orientation
+                let direction = new_lookat - screen_data.camera.position;
+                screen_data.camera.rotation = look_rotation(direction, Vec3::new(0.0, 1.0, 0.0)).inverse();
             }
         }
     }
 
     pub fn cleanup(world: &mut World) {}
 
+    fn handle_player_deaths(world: &mut World) {
+        let (died, player) = {
+            let (bus, mut state) = <(Read<EventBus>, Write<GameState>)>::fetch(&world.resources);
+            let mut died = false;
+            for event in bus.read_typed::<PlayerDied>(&mut state.player_died_reader) {
+                match event {
+                    Ok(event) => {
+                        println!("Player died: {}", event.cause);
+                        died = true;
+                    }
+                    Err(error) => println!("{}", error),
+                }
+            }
+            (died, state.player)
+        };
+        if died {
+            if let Err(error) = TransformHierarchy::set_local_position(world, player, Vec3::ZERO) {
+                println!("Couldn't reset player: {}", error);
+            }
+        }
+    }
+
     fn handle_player_input(world: &mut World, input: Input) {
         let state = world.resources.get::<GameState>().unwrap();
         let id = state.player;
-        let pos = TransformHierarchy::get_local_position(world, id);
+        let pos = match TransformHierarchy::get_local_position(world, id) {
+            Ok(pos) => pos,
+            Err(_) => return,
+        };
 
         let vertical = if input.move_up { 1.0 } else { -1.0 } + if input.move_down { -1.0 } else { 1.0 };
         let horizontal = if input.move_right { 1.0 } else { -1.0 } + if input.move_left { -1.0 } else { 1.0 };
@@ -159,6 +207,6 @@ impl GameLogic {
         let new_pos = pos + Vec3::new(horizontal, 0.0, vertical) * 0.05;
 
         drop(state);
-        TransformHierarchy::set_local_position(world, id, new_pos);
+        TransformHierarchy::set_local_position(world, id, new_pos).unwrap();
     }
 }
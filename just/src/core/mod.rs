@@ -4,9 +4,11 @@ mod time;
 
 use just_wgpu::winit::event::{ElementState, Event, MouseButton, VirtualKeyCode, WindowEvent};
 use just_wgpu::winit::event_loop::ControlFlow;
-use time::TimeSystem;
+pub use time::{TimeData, TimeSystem};
 
+use just_core::commands::Commands;
 use just_core::math::Vec2;
+use just_core::transform::TransformSystem;
 use just_core::{game_object, hierarchy};
 use just_input::InputSystem;
 
@@ -15,6 +17,7 @@ use just_wgpu::RenderingSystem;
 use winit::event_loop::EventLoop;
 
 use just_core::ecs::prelude::*;
+use just_core::events::EventBus;
 
 use just_assets::AssetSystem;
 
@@ -23,6 +26,9 @@ pub use hierarchy::TransformHierarchy;
 
 use game::GameLogic;
 
+use crate::scene::{PrefabOverrides, SceneError, SceneFormat, SceneId, SceneSystem, ScriptName};
+use crate::scripting::ScriptSystem;
+
 struct Animator;
 
 struct Audio;
@@ -30,6 +36,7 @@ struct Audio;
 pub struct Engine {
     event_loop: Option<EventLoop<()>>,
     pub world: World,
+    scripts: ScriptSystem,
 }
 
 #[derive(Debug)]
@@ -45,24 +52,34 @@ impl std::ops::Drop for Engine {
 }
 
 impl Engine {
-    pub fn new(res_path: &str) -> Self {
+    pub fn new(res_path: &str, scripts_path: &str) -> Self {
         let mut world = World::default();
         let event_loop = EventLoop::<()>::new();
         AssetSystem::initialize(&mut world, res_path);
         InputSystem::initialize(&mut world);
+        world.resources.insert(EventBus::default());
         RenderingSystem::initialize(&mut world, &event_loop);
         GameObject::initialize(&mut world);
         TimeSystem::initialize(&mut world);
+        SceneSystem::initialize(&mut world);
+        {
+            let mut commands = world.resources.get_mut::<Commands>().unwrap();
+            commands.register_cloner(RenderingSystem::clone_renderable);
+            commands.register_renderable_creator(RenderingSystem::add_renderable);
+            commands.register_clone::<ScriptName>();
+        }
 
-        let mut eng = Engine {
-            event_loop: Some(event_loop),
-            world,
-        };
+        TransformSystem::update(&mut world);
+        RenderingSystem::update(&mut world);
 
-        RenderingSystem::update(&mut eng.world);
+        GameLogic::initialize(&mut world);
+        let scripts = ScriptSystem::initialize(&mut world, scripts_path);
 
-        GameLogic::initialize(&mut eng.world);
-        eng
+        Engine {
+            event_loop: Some(event_loop),
+            world,
+            scripts,
+        }
     }
 
     pub fn run(mut self) {
@@ -160,9 +177,24 @@ impl Engine {
         TimeSystem::update(&mut self.world);
         AssetSystem::update(&mut self.world);
         GameLogic::update(&mut self.world);
-        GameObject::remove_marked(&mut self.world);
+        self.sync();
+        self.scripts.update(&mut self.world);
+        self.sync();
+        let scripts = &mut self.scripts;
+        GameObject::remove_marked_with(&mut self.world, |world, id| scripts.on_destroy(world, id));
+        self.sync();
+        TransformSystem::update(&mut self.world);
         RenderingSystem::update(&mut self.world);
     }
+
+    /// Sync point, structural changes recorded in `Commands` by preceding systems are applied here.
+    /// Objects reparented by them or by preceding systems get `onParentChanged` before next system runs.
+    fn sync(&mut self) {
+        for error in Commands::apply(&mut self.world) {
+            println!("Couldn't apply command: {}", error);
+        }
+        self.scripts.dispatch_hierarchy_events(&mut self.world);
+    }
 }
 
 impl Engine {
@@ -173,4 +205,34 @@ impl Engine {
     pub fn create_game_object(&mut self) -> Entity {
         GameObject::create_empty(&mut self.world)
     }
+
+    pub fn load_scene(&mut self, name: &str) -> Result<SceneId, SceneError> {
+        SceneSystem::load(&mut self.world, name)
+    }
+
+    pub fn load_scene_additive(&mut self, name: &str) -> Result<SceneId, SceneError> {
+        SceneSystem::load_additive(&mut self.world, name)
+    }
+
+    pub fn unload_scene(&mut self, id: SceneId) -> Result<(), SceneError> {
+        SceneSystem::unload(&mut self.world, id)
+    }
+
+    pub fn instantiate_prefab(
+        &mut self,
+        name: &str,
+        parent: Option<Entity>,
+        overrides: PrefabOverrides,
+    ) -> Result<Entity, SceneError> {
+        SceneSystem::instantiate_prefab(&mut self.world, name, parent, overrides)
+    }
+
+    pub fn save_scene<P: AsRef<std::path::Path>>(
+        &self,
+        name: &str,
+        path: P,
+        format: SceneFormat,
+    ) -> Result<(), SceneError> {
+        SceneSystem::save(&self.world, name, path, format)
+    }
 }
@@ -1,9 +1,12 @@
 use super::Engine;
 use super::TransformHierarchy;
 use just_core::ecs::prelude::Entity;
+use just_core::hierarchy::HierarchyError;
 
 impl Engine {
-    pub fn set_parent(&mut self, obj: Entity, new_parent: Option<Entity>) -> Result<(), ()> {
-        TransformHierarchy::set_parent(&mut self.world, obj, new_parent)
+    pub fn set_parent(&mut self, obj: Entity, new_parent: Option<Entity>) -> Result<(), HierarchyError> {
+        TransformHierarchy::set_parent(&mut self.world, obj, new_parent)?;
+        self.scripts.dispatch_hierarchy_events(&mut self.world);
+        Ok(())
     }
 }
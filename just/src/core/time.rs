@@ -1,9 +1,27 @@
 use just_core::ecs::prelude::*;
 
-struct TimeData {
+pub struct TimeData {
     start: std::time::Instant,
     elapsed: f32,
     dt: f32,
+    frame: u64,
+}
+
+impl TimeData {
+    /// Seconds since engine start, measured at the beginning of current frame.
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// Seconds between beginning of previous and current frame.
+    pub fn dt(&self) -> f32 {
+        self.dt
+    }
+
+    /// Number of frames started so far, first frame is 1.
+    pub fn frame_count(&self) -> u64 {
+        self.frame
+    }
 }
 
 pub struct TimeSystem;
@@ -14,6 +32,7 @@ impl TimeSystem {
             start: std::time::Instant::now(),
             elapsed: 0f32,
             dt: 0.016f32,
+            frame: 0,
         };
         world.resources.insert(system);
     }
@@ -26,5 +45,6 @@ impl TimeSystem {
         let dt = elapsed - sys.elapsed as f64;
         sys.dt = dt as f32;
         sys.elapsed = elapsed as f32;
+        sys.frame += 1;
     }
 }
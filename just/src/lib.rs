@@ -1,3 +1,5 @@
 #![allow(dead_code)]
 
 pub mod core;
+pub mod scene;
+pub mod scripting;
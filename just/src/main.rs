@@ -2,10 +2,37 @@
 
 use just::*;
 
+// lets `ScriptLimits::memory` be enforced for each script controller
+#[global_allocator]
+static ALLOCATOR: scripting::ScriptAllocator = scripting::ScriptAllocator;
+
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("schema") => {
+            let path = args.get(2).map(String::as_str).unwrap_or("schemas/scene.schema");
+            std::fs::write(path, scene::SceneSystem::json_schema()).unwrap();
+            println!("scene schema written to {}", path);
+            return;
+        }
+        Some("typings") => {
+            let path = args.get(2).map(String::as_str).unwrap_or("dev_app/scripts/just.d.ts");
+            std::fs::write(path, scripting::ScriptSystem::typings()).unwrap();
+            println!("script typings written to {}", path);
+            return;
+        }
+        _ => (),
+    }
+
     let _window_config = 1i32;
     let _renderer_config = 2i32;
     let _resources = 3i32;
 
-    core::Engine::new(&"dev_app/res").run();
+    let mut engine = core::Engine::new("dev_app/res", "dev_app/scripts");
+    if let Some(scene) = args.get(1) {
+        if let Err(error) = engine.load_scene(scene) {
+            println!("couldn't load scene:\n{}", error);
+        }
+    }
+    engine.run();
 }
@@ -0,0 +1,60 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// `schemas/scene.schema` is generated from these types with `just schema`,
+// doc comments on fields end up in it as descriptions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Scene {
+    pub name: String,
+    pub camera_rotation: [f32; 3],
+    pub viewport_height: f32,
+    pub objects: Vec<Object>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Object {
+    pub name: String,
+    /// Name of prefab this object is an instance of.
+    /// Transform, renderable and script given next to it override values from the prefab.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefab: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<[f32; 3]>,
+    /// Euler angles in XYZ order, same as camera_rotation of the scene.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rotation: Option<[f32; 3]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scale: Option<[f32; 3]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub renderable: Option<Renderable>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub children: Option<Vec<Object>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Renderable {
+    pub mesh: String,
+    pub texture: String,
+}
+
+/// Serialization format of a scene file.
+/// Both are stored with the same extension, format is recognized by the first character of the file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SceneFormat {
+    Json,
+    Ron,
+}
+
+impl SceneFormat {
+    pub fn detect(text: &str) -> Self {
+        match text.trim_start().chars().next() {
+            Some('{') => SceneFormat::Json,
+            _ => SceneFormat::Ron,
+        }
+    }
+}
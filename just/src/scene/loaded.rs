@@ -0,0 +1,140 @@
+use super::{SceneError, SceneSystem};
+use just_core::ecs::prelude::*;
+use just_core::events::{Event, EventBus};
+use just_core::game_object::GameObject;
+use just_core::hierarchy::TransformHierarchy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SceneId(u32);
+
+/// Added to every object created by loading a scene.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SceneTag(pub SceneId);
+
+struct LoadedScene {
+    name: String,
+    roots: Vec<Entity>,
+}
+
+/// Sent through `EventBus` after scene is loaded, also additively.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelLoaded {
+    pub scene: String,
+}
+
+impl Event for LevelLoaded {
+    const NAME: &'static str = "level_loaded";
+}
+
+#[derive(Default)]
+pub struct LoadedScenes {
+    last_id: u32,
+    scenes: HashMap<SceneId, LoadedScene>,
+}
+
+impl SceneSystem {
+    /// Loads scene and applies its camera settings. Scenes loaded earlier are kept.
+    pub fn load(world: &mut World, name: &str) -> Result<SceneId, SceneError> {
+        let scene = Self::read(world, name)?;
+        Self::apply_camera(world, &scene);
+        let roots = Self::instantiate_objects(world, &scene)?;
+        let id = Self::register(world, name, roots);
+        Self::announce(world, name);
+        Ok(id)
+    }
+
+    /// Loads scene next to already loaded ones, leaving camera untouched.
+    pub fn load_additive(world: &mut World, name: &str) -> Result<SceneId, SceneError> {
+        let scene = Self::read(world, name)?;
+        let roots = Self::instantiate_objects(world, &scene)?;
+        let id = Self::register(world, name, roots);
+        Self::announce(world, name);
+        Ok(id)
+    }
+
+    /// Marks every object created by the scene for deletion.
+    /// Objects from outside of the scene attached to them are detached instead of being deleted.
+    pub fn unload(world: &mut World, id: SceneId) -> Result<(), SceneError> {
+        world
+            .resources
+            .get_mut::<LoadedScenes>()
+            .unwrap()
+            .scenes
+            .remove(&id)
+            .ok_or(SceneError::NotLoadedId(id))?;
+
+        let members = Self::scene_members(world, id);
+        for member in members.iter().copied() {
+            let children = world.get_component::<GameObject>(member).unwrap().children.clone();
+            for child in children {
+                let child_scene = world.get_component::<SceneTag>(child).map(|tag| tag.0);
+                if child_scene != Some(id) {
+                    TransformHierarchy::set_parent(world, child, None)?;
+                }
+            }
+        }
+        for member in members {
+            GameObject::delete(world, member);
+        }
+        Ok(())
+    }
+
+    pub fn scene_members(world: &World, id: SceneId) -> Vec<Entity> {
+        Read::<SceneTag>::query()
+            .iter_entities_immutable(world)
+            .filter(|(_, tag)| tag.0 == id)
+            .map(|(entity, _)| entity)
+            .collect()
+    }
+
+    pub fn scene_roots(world: &World, id: SceneId) -> Option<Vec<Entity>> {
+        let loaded = world.resources.get::<LoadedScenes>().unwrap();
+        loaded.scenes.get(&id).map(|scene| scene.roots.clone())
+    }
+
+    pub fn loaded_scenes(world: &World) -> Vec<(SceneId, String)> {
+        let loaded = world.resources.get::<LoadedScenes>().unwrap();
+        loaded
+            .scenes
+            .iter()
+            .map(|(id, scene)| (*id, scene.name.clone()))
+            .collect()
+    }
+
+    fn announce(world: &mut World, name: &str) {
+        if let Some(mut bus) = world.resources.get_mut::<EventBus>() {
+            let event = LevelLoaded { scene: name.to_owned() };
+            bus.send(&event).unwrap();
+        }
+    }
+
+    fn register(world: &mut World, name: &str, roots: Vec<Entity>) -> SceneId {
+        let id = {
+            let mut loaded = world.resources.get_mut::<LoadedScenes>().unwrap();
+            loaded.last_id += 1;
+            SceneId(loaded.last_id)
+        };
+        for root in roots.iter().copied() {
+            Self::tag_hierarchy(world, root, id);
+        }
+        let mut loaded = world.resources.get_mut::<LoadedScenes>().unwrap();
+        loaded.scenes.insert(
+            id,
+            LoadedScene {
+                name: name.to_owned(),
+                roots,
+            },
+        );
+        id
+    }
+
+    fn tag_hierarchy(world: &mut World, id: Entity, scene: SceneId) {
+        world.add_component(id, SceneTag(scene));
+        let children = world.get_component::<GameObject>(id).unwrap().children.clone();
+        for child in children {
+            Self::tag_hierarchy(world, child, scene);
+        }
+    }
+}
@@ -0,0 +1,313 @@
+mod data;
+mod loaded;
+mod prefab;
+mod save;
+mod validation;
+
+pub use data::{Object, Renderable, Scene, SceneFormat};
+pub use loaded::{LevelLoaded, LoadedScenes, SceneId, SceneTag};
+pub use prefab::{Prefab, PrefabChild, PrefabInstance, PrefabOverrides, PREFAB_EXTENSION};
+pub use validation::{locate, parse_file, PathSegment, ValidationError, ValuePath};
+
+use just_assets::{AssetManager, AssetStorage};
+use just_core::ecs::prelude::*;
+use just_core::events::EventBus;
+use just_core::game_object::GameObject;
+use just_core::glam::EulerRot;
+use just_core::hierarchy::{HierarchyError, TransformHierarchy};
+use just_core::math::{Quat, Vec3};
+use just_wgpu::{Mesh, RenderingSystem, ScreenData, Texture};
+use validation::References;
+
+pub const SCENE_EXTENSION: &str = "scene";
+
+/// Contents of a scene file found in asset directory.
+/// Parsed only when scene is loaded, so that edits are picked up on every load.
+pub struct SceneFile {
+    pub text: String,
+}
+
+/// Script requested for object in scene file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptName(pub String);
+
+#[derive(Debug)]
+pub enum SceneError {
+    NotFound(String),
+    NotLoaded(String),
+    PrefabNotFound(String),
+    PrefabCycle(Vec<String>),
+    NotLoadedId(SceneId),
+    Invalid(Vec<ValidationError>),
+    Serialize { scene: String, message: String },
+    Io { path: std::path::PathBuf, message: String },
+    Hierarchy(HierarchyError),
+}
+
+impl From<HierarchyError> for SceneError {
+    fn from(error: HierarchyError) -> Self {
+        SceneError::Hierarchy(error)
+    }
+}
+
+impl std::fmt::Display for SceneError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SceneError::NotFound(name) => write!(f, "scene '{}' not found", name),
+            SceneError::NotLoaded(name) => write!(f, "scene '{}' is not loaded yet", name),
+            SceneError::PrefabNotFound(name) => write!(f, "prefab '{}' not found", name),
+            SceneError::PrefabCycle(chain) => write!(
+                f,
+                "prefab '{}' contains itself: {}",
+                chain.last().unwrap(),
+                chain.join(" -> ")
+            ),
+            SceneError::NotLoadedId(id) => write!(f, "no scene loaded with id {:?}", id),
+            SceneError::Invalid(errors) => {
+                for (i, error) in errors.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}", error)?;
+                }
+                Ok(())
+            }
+            SceneError::Serialize { scene, message } => write!(f, "couldn't serialize scene '{}': {}", scene, message),
+            SceneError::Io { path, message } => write!(f, "{}: {}", path.display(), message),
+            SceneError::Hierarchy(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+pub struct SceneSystem;
+
+impl SceneSystem {
+    pub fn initialize(world: &mut World) {
+        // resources are borrowed until the block ends, new ones are inserted after it
+        let (storage, prefabs) = {
+            let (mut manager, meshes, textures) = <(
+                Write<AssetManager>,
+                Read<AssetStorage<Mesh>>,
+                Read<AssetStorage<Texture>>,
+            )>::fetch(&world.resources);
+            let mut storage = AssetStorage::<SceneFile>::empty(&manager, &[SCENE_EXTENSION]);
+            storage.process(&mut manager, SCENE_EXTENSION, |data, _name| {
+                let text = String::from_utf8_lossy(data).into_owned();
+                (SceneFile { text }, false)
+            });
+            let references = References {
+                meshes: &meshes,
+                textures: &textures,
+                prefabs: None,
+            };
+            let mut prefabs = AssetStorage::<Prefab>::empty(&manager, &[PREFAB_EXTENSION]);
+            let mut texts = vec![];
+            prefabs.try_process(&mut manager, PREFAB_EXTENSION, |data, name| {
+                let file = format!("{}.{}", name, PREFAB_EXTENSION);
+                let text = String::from_utf8_lossy(data).into_owned();
+                let root: Object = validation::parse_file(&file, &text).map_err(|e| SceneError::Invalid(vec![e]))?;
+                let errors = validation::check_prefab(&file, &text, &root, &references);
+                if errors.is_empty() {
+                    texts.push((name.to_owned(), file, text));
+                    Ok(Prefab { root })
+                } else {
+                    Err(SceneError::Invalid(errors))
+                }
+            });
+            Self::check_nested_prefabs(&mut prefabs, &meshes, &textures, texts);
+            (storage, prefabs)
+        };
+        world.resources.insert(storage);
+        world.resources.insert(prefabs);
+        world.resources.insert(LoadedScenes::default());
+        if let Some(mut bus) = world.resources.get_mut::<EventBus>() {
+            bus.declare_type::<LevelLoaded>();
+        }
+    }
+
+    /// Prefabs can use each other, so their references are checked once all of them are loaded.
+    /// Invalid ones are unloaded, until none of the remaining ones uses an unloaded prefab.
+    fn check_nested_prefabs(
+        prefabs: &mut AssetStorage<Prefab>,
+        meshes: &AssetStorage<Mesh>,
+        textures: &AssetStorage<Texture>,
+        mut texts: Vec<(String, String, String)>,
+    ) {
+        loop {
+            let mut invalid = vec![];
+            for (i, (name, file, text)) in texts.iter().enumerate() {
+                let references = References {
+                    meshes,
+                    textures,
+                    prefabs: Some(&*prefabs),
+                };
+                let root = &prefabs.get_value(&prefabs.get_handle(name).unwrap()).unwrap().root;
+                let errors = validation::check_prefab(file, text, root, &references);
+                if !errors.is_empty() {
+                    println!("couldn't load {}: {}", file, SceneError::Invalid(errors));
+                    invalid.push(i);
+                }
+            }
+            if invalid.is_empty() {
+                return;
+            }
+            for i in invalid.into_iter().rev() {
+                let (name, ..) = texts.remove(i);
+                prefabs.set_offline(&name);
+            }
+        }
+    }
+
+    /// JSON schema of scene files, written to `schemas/scene.schema` by `just schema`.
+    pub fn json_schema() -> String {
+        let schema = schemars::schema_for!(Scene);
+        serde_json::to_string_pretty(&schema).unwrap()
+    }
+
+    /// Parses scene file and checks that all referenced assets exist.
+    /// `file` is used only for error reporting.
+    pub fn parse(world: &World, file: &str, text: &str) -> Result<Scene, SceneError> {
+        let scene: Scene = validation::parse_file(file, text).map_err(|e| SceneError::Invalid(vec![e]))?;
+        let (meshes, textures, prefabs) = <(
+            Read<AssetStorage<Mesh>>,
+            Read<AssetStorage<Texture>>,
+            Read<AssetStorage<Prefab>>,
+        )>::fetch(&world.resources);
+        let references = References {
+            meshes: &meshes,
+            textures: &textures,
+            prefabs: Some(&prefabs),
+        };
+        let errors = validation::check_scene(file, text, &scene, &references);
+        if errors.is_empty() {
+            Ok(scene)
+        } else {
+            Err(SceneError::Invalid(errors))
+        }
+    }
+
+    /// Reads and validates scene from asset directory by its name (file name without extension).
+    pub fn read(world: &World, name: &str) -> Result<Scene, SceneError> {
+        let storage = world.resources.get::<AssetStorage<SceneFile>>().unwrap();
+        let handle = storage
+            .get_handle(name)
+            .ok_or_else(|| SceneError::NotFound(name.to_owned()))?;
+        let file = storage
+            .get_value(&handle)
+            .ok_or_else(|| SceneError::NotLoaded(name.to_owned()))?;
+        let file_name = format!("{}.{}", name, SCENE_EXTENSION);
+        Self::parse(world, &file_name, &file.text)
+    }
+
+    /// Creates objects of scene and applies its camera settings.
+    /// Created objects are not tagged with scene id, see `SceneSystem::load` for that.
+    pub fn instantiate(world: &mut World, scene: &Scene) -> Result<Vec<Entity>, SceneError> {
+        Self::apply_camera(world, scene);
+        Self::instantiate_objects(world, scene)
+    }
+
+    pub fn apply_camera(world: &mut World, scene: &Scene) {
+        let mut screen_data = world.resources.get_mut::<ScreenData>().unwrap();
+        let [x, y, z] = scene.camera_rotation;
+        screen_data.camera.rotation = Quat::from_euler(EulerRot::XYZ, x, y, z);
+        screen_data.viewport.camera_lens_height = scene.viewport_height;
+    }
+
+    /// Objects created before one of them failed are deleted, so no untagged objects are left behind.
+    fn instantiate_objects(world: &mut World, scene: &Scene) -> Result<Vec<Entity>, SceneError> {
+        let mut created = vec![];
+        for object in scene.objects.iter() {
+            match Self::instantiate_object(world, object, None) {
+                Ok(id) => created.push(id),
+                Err(error) => {
+                    for id in created {
+                        GameObject::delete(world, id);
+                    }
+                    return Err(error);
+                }
+            }
+        }
+        Ok(created)
+    }
+
+    /// Creates object with its children. If it fails, already created part is marked for deletion.
+    pub fn instantiate_object(
+        world: &mut World,
+        object: &Object,
+        parent: Option<Entity>,
+    ) -> Result<Entity, SceneError> {
+        Self::build_object(world, object, parent, &mut vec![])
+    }
+
+    fn build_object(
+        world: &mut World,
+        object: &Object,
+        parent: Option<Entity>,
+        expanding: &mut Vec<String>,
+    ) -> Result<Entity, SceneError> {
+        if let Some(prefab) = &object.prefab {
+            let overrides = PrefabOverrides::from_object(object);
+            let id = Self::build_prefab_instance(world, prefab, parent, overrides, expanding)?;
+            let children = object
+                .children
+                .iter()
+                .flatten()
+                .try_for_each(|child| Self::build_object(world, child, Some(id), expanding).map(drop));
+            return Self::delete_on_error(world, id, children);
+        }
+
+        let id = GameObject::create_empty(world);
+        let filled = Self::attach(world, id, parent).and_then(|()| Self::fill_object(world, id, object, expanding));
+        Self::delete_on_error(world, id, filled)
+    }
+
+    fn attach(world: &mut World, id: Entity, parent: Option<Entity>) -> Result<(), SceneError> {
+        if parent.is_some() {
+            TransformHierarchy::set_parent(world, id, parent)?;
+        }
+        Ok(())
+    }
+
+    /// Deleting object removes its descendants too, so whatever was created under it is cleaned up.
+    fn delete_on_error(world: &mut World, id: Entity, result: Result<(), SceneError>) -> Result<Entity, SceneError> {
+        if result.is_err() {
+            GameObject::delete(world, id);
+        }
+        result.map(|()| id)
+    }
+
+    /// Local position, rotation and scale given by object, defaults for missing ones.
+    fn object_transform(object: &Object) -> (Vec3, Quat, Vec3) {
+        let position = object.position.map(Vec3::from).unwrap_or(Vec3::ZERO);
+        let rotation = object
+            .rotation
+            .map(|[x, y, z]| Quat::from_euler(EulerRot::XYZ, x, y, z))
+            .unwrap_or(Quat::IDENTITY);
+        let scale = object.scale.map(Vec3::from).unwrap_or(Vec3::ONE);
+        (position, rotation, scale)
+    }
+
+    /// Sets up already existing game object with data from scene and creates its children.
+    fn fill_object(
+        world: &mut World,
+        id: Entity,
+        object: &Object,
+        expanding: &mut Vec<String>,
+    ) -> Result<(), SceneError> {
+        GameObject::set_name(world, id, object.name.clone())?;
+        let (position, rotation, scale) = Self::object_transform(object);
+        TransformHierarchy::set_local_position(world, id, position)?;
+        TransformHierarchy::set_local_rotation(world, id, rotation)?;
+        TransformHierarchy::set_local_scale(world, id, scale)?;
+        if let Some(renderable) = &object.renderable {
+            RenderingSystem::add_renderable(world, id, &renderable.mesh, &renderable.texture);
+        }
+        if let Some(script) = &object.script {
+            world.add_component(id, ScriptName(script.clone()));
+        }
+        for child in object.children.iter().flatten() {
+            Self::build_object(world, child, Some(id), expanding)?;
+        }
+        Ok(())
+    }
+}
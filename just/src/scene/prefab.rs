@@ -0,0 +1,179 @@
+use super::{Object, Renderable, SceneError, SceneSystem, ScriptName};
+use just_assets::AssetStorage;
+use just_core::ecs::prelude::*;
+use just_core::game_object::GameObject;
+use just_core::math::{Quat, Vec3};
+
+pub const PREFAB_EXTENSION: &str = "prefab";
+
+/// Reusable object hierarchy, stored in the same format as objects of a scene.
+pub struct Prefab {
+    pub root: Object,
+}
+
+/// Values replacing ones defined in prefab, applied to root of an instance.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PrefabOverrides {
+    pub name: Option<String>,
+    pub position: Option<[f32; 3]>,
+    pub rotation: Option<[f32; 3]>,
+    pub scale: Option<[f32; 3]>,
+    pub renderable: Option<Renderable>,
+    pub script: Option<String>,
+}
+
+impl PrefabOverrides {
+    /// Reads overrides written next to `prefab` field of scene object.
+    pub fn from_object(object: &Object) -> Self {
+        Self {
+            name: Some(object.name.clone()),
+            position: object.position,
+            rotation: object.rotation,
+            scale: object.scale,
+            renderable: object.renderable.clone(),
+            script: object.script.clone(),
+        }
+    }
+
+    fn apply(&self, prefab: &Object) -> Object {
+        let mut object = prefab.clone();
+        if let Some(name) = &self.name {
+            object.name = name.clone();
+        }
+        object.position = self.position.or(object.position);
+        object.rotation = self.rotation.or(object.rotation);
+        object.scale = self.scale.or(object.scale);
+        if self.renderable.is_some() {
+            object.renderable = self.renderable.clone();
+        }
+        if self.script.is_some() {
+            object.script = self.script.clone();
+        }
+        object
+    }
+}
+
+/// Marks root of a prefab instance, so that prefab changes can be applied to it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrefabInstance {
+    pub prefab: String,
+    pub overrides: PrefabOverrides,
+}
+
+/// Marks children of prefab instance created from prefab definition, holds name of the prefab.
+/// Children added below the instance by scene or at runtime don't have it, so they're kept by `apply_prefab`
+/// and saved with the instance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrefabChild(pub String);
+
+impl SceneSystem {
+    pub fn instantiate_prefab(
+        world: &mut World,
+        name: &str,
+        parent: Option<Entity>,
+        overrides: PrefabOverrides,
+    ) -> Result<Entity, SceneError> {
+        Self::build_prefab_instance(world, name, parent, overrides, &mut vec![])
+    }
+
+    /// `expanding` are prefabs being instantiated further up, prefab found among them contains itself.
+    pub(super) fn build_prefab_instance(
+        world: &mut World,
+        name: &str,
+        parent: Option<Entity>,
+        overrides: PrefabOverrides,
+        expanding: &mut Vec<String>,
+    ) -> Result<Entity, SceneError> {
+        if expanding.iter().any(|expanded| expanded == name) {
+            let mut chain = expanding.clone();
+            chain.push(name.to_owned());
+            return Err(SceneError::PrefabCycle(chain));
+        }
+        let object = Self::prefab_with_overrides(world, name, &overrides)?;
+        let id = GameObject::create_empty(world);
+        expanding.push(name.to_owned());
+        let filled =
+            Self::attach(world, id, parent).and_then(|()| Self::fill_prefab(world, id, name, &object, expanding));
+        expanding.pop();
+        Self::delete_on_error(world, id, filled)?;
+        world.add_component(
+            id,
+            PrefabInstance {
+                prefab: name.to_owned(),
+                overrides,
+            },
+        );
+        Ok(id)
+    }
+
+    /// Fills instance root, children it creates are marked with `PrefabChild`.
+    fn fill_prefab(
+        world: &mut World,
+        id: Entity,
+        name: &str,
+        object: &Object,
+        expanding: &mut Vec<String>,
+    ) -> Result<(), SceneError> {
+        let previous = world.get_component::<GameObject>(id).unwrap().children.clone();
+        Self::fill_object(world, id, object, expanding)?;
+        let children = world.get_component::<GameObject>(id).unwrap().children.clone();
+        for child in children.into_iter().filter(|child| !previous.contains(child)) {
+            world.add_component(child, PrefabChild(name.to_owned()));
+        }
+        Ok(())
+    }
+
+    /// Replaces prefab definition. Existing instances are not changed until `apply_prefab` is called.
+    pub fn set_prefab(world: &mut World, name: &str, root: Object) {
+        let mut prefabs = world.resources.get_mut::<AssetStorage<Prefab>>().unwrap();
+        prefabs.set_loaded(name, Prefab { root });
+    }
+
+    pub fn prefab_instances(world: &World, name: &str) -> Vec<Entity> {
+        Read::<PrefabInstance>::query()
+            .iter_entities_immutable(world)
+            .filter(|(_, instance)| instance.prefab == name)
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// Rebuilds every instance of prefab from its current definition, keeping per-instance overrides.
+    /// Instance roots keep their ids, children that came from prefab are recreated,
+    /// ones added below the instance by scene or at runtime are kept.
+    /// Returns number of updated instances.
+    pub fn apply_prefab(world: &mut World, name: &str) -> Result<usize, SceneError> {
+        let instances = Self::prefab_instances(world, name);
+        for id in instances.iter().copied() {
+            let overrides = world.get_component::<PrefabInstance>(id).unwrap().overrides.clone();
+            let object = Self::prefab_with_overrides(world, name, &overrides)?;
+
+            let children = world.get_component::<GameObject>(id).unwrap().children.clone();
+            for child in children {
+                if world.get_component::<PrefabChild>(child).is_some() {
+                    GameObject::remove_game_object(world, child)?;
+                }
+            }
+            world.remove_component::<just_wgpu::Renderable>(id);
+            world.remove_component::<ScriptName>(id);
+
+            Self::fill_prefab(world, id, name, &object, &mut vec![name.to_owned()])?;
+        }
+        Ok(instances.len())
+    }
+
+    fn prefab_with_overrides(world: &World, name: &str, overrides: &PrefabOverrides) -> Result<Object, SceneError> {
+        let prefabs = world.resources.get::<AssetStorage<Prefab>>().unwrap();
+        let prefab = prefabs
+            .get_handle(name)
+            .and_then(|handle| prefabs.get_value(&handle))
+            .ok_or_else(|| SceneError::PrefabNotFound(name.to_owned()))?;
+        Ok(overrides.apply(&prefab.root))
+    }
+
+    /// Transform of prefab root, which instances have unless it's overridden. `None` if prefab isn't loaded.
+    pub(super) fn prefab_transform(world: &World, name: &str) -> Option<(Vec3, Quat, Vec3)> {
+        let prefabs = world.resources.get::<AssetStorage<Prefab>>().unwrap();
+        let prefab = prefabs.get_handle(name).and_then(|handle| prefabs.get_value(&handle))?;
+        Some(Self::object_transform(&prefab.root))
+    }
+}
@@ -0,0 +1,130 @@
+use super::{Object, PrefabChild, PrefabInstance, Renderable, Scene, SceneError, SceneFormat, SceneSystem, ScriptName};
+use just_core::ecs::prelude::*;
+use just_core::game_object::GameObject;
+use just_core::glam::EulerRot;
+use just_core::hierarchy::TransformHierarchy;
+use just_core::math::{Quat, Vec3};
+use just_wgpu::ScreenData;
+
+impl SceneSystem {
+    /// Captures every root object (and its children) currently present in the world, in order of roots.
+    pub fn capture(world: &World, name: &str) -> Result<Scene, SceneError> {
+        let roots = TransformHierarchy::roots(world);
+
+        let screen_data = world.resources.get::<ScreenData>().unwrap();
+        let (x, y, z) = screen_data.camera.rotation.to_euler(EulerRot::XYZ);
+
+        Ok(Scene {
+            name: name.to_owned(),
+            camera_rotation: [x, y, z],
+            viewport_height: screen_data.viewport.camera_lens_height,
+            objects: roots
+                .into_iter()
+                .map(|id| Self::capture_object(world, id))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    pub fn capture_object(world: &World, id: Entity) -> Result<Object, SceneError> {
+        let position = TransformHierarchy::get_local_position(world, id)?;
+        let rotation = TransformHierarchy::get_local_rotation(world, id)?;
+        let scale = TransformHierarchy::get_local_scale(world, id)?;
+        let renderable = world
+            .get_component::<just_wgpu::Renderable>(id)
+            .map(|renderable| Renderable {
+                mesh: renderable.mesh_name().to_owned(),
+                texture: renderable.texture_name().to_owned(),
+            });
+        let script = world.get_component::<ScriptName>(id).map(|script| script.0.clone());
+
+        // prefab instances are written as reference to prefab, with current transform and overrides
+        let prefab = world
+            .get_component::<PrefabInstance>(id)
+            .map(|instance| (*instance).clone());
+        let (prefab, renderable, script) = match prefab {
+            None => (None, renderable, script),
+            Some(instance) => (
+                Some(instance.prefab),
+                instance.overrides.renderable,
+                instance.overrides.script,
+            ),
+        };
+
+        // children that came from prefab are created by it again
+        let children: Vec<Entity> = world
+            .get_component::<GameObject>(id)
+            .unwrap()
+            .children
+            .iter()
+            .copied()
+            .filter(|child| world.get_component::<PrefabChild>(*child).is_none())
+            .collect();
+        let children = if children.is_empty() {
+            None
+        } else {
+            Some(
+                children
+                    .into_iter()
+                    .map(|child| Self::capture_object(world, child))
+                    .collect::<Result<_, _>>()?,
+            )
+        };
+
+        // values equal to ones object gets anyway are left out, for instances those are values of prefab
+        let defaults = match &prefab {
+            None => Some((Vec3::ZERO, Quat::IDENTITY, Vec3::ONE)),
+            Some(prefab) => Self::prefab_transform(world, prefab),
+        };
+        let (position, rotation, scale) = match defaults {
+            Some((default_position, default_rotation, default_scale)) => (
+                Some(position).filter(|position| *position != default_position),
+                Some(rotation).filter(|rotation| *rotation != default_rotation),
+                Some(scale).filter(|scale| *scale != default_scale),
+            ),
+            None => (Some(position), Some(rotation), Some(scale)),
+        };
+
+        Ok(Object {
+            name: GameObject::get_name(world, id)?,
+            prefab,
+            position: position.map(Into::into),
+            rotation: rotation.map(|rotation| {
+                let (x, y, z) = rotation.to_euler(EulerRot::XYZ);
+                [x, y, z]
+            }),
+            scale: scale.map(Into::into),
+            renderable,
+            script,
+            children,
+        })
+    }
+
+    pub fn serialize(scene: &Scene, format: SceneFormat) -> Result<String, SceneError> {
+        let result = match format {
+            SceneFormat::Json => serde_json::to_string_pretty(scene).map_err(|e| e.to_string()),
+            SceneFormat::Ron => {
+                ron::ser::to_string_pretty(scene, ron::ser::PrettyConfig::default()).map_err(|e| e.to_string())
+            }
+        };
+        result.map_err(|message| SceneError::Serialize {
+            scene: scene.name.clone(),
+            message,
+        })
+    }
+
+    /// Writes current world state to a scene file, loadable with `SceneSystem::load`
+    /// once placed in the asset directory.
+    pub fn save<P: AsRef<std::path::Path>>(
+        world: &World,
+        name: &str,
+        path: P,
+        format: SceneFormat,
+    ) -> Result<(), SceneError> {
+        let scene = Self::capture(world, name)?;
+        let text = Self::serialize(&scene, format)?;
+        std::fs::write(path.as_ref(), text).map_err(|e| SceneError::Io {
+            path: path.as_ref().to_path_buf(),
+            message: e.to_string(),
+        })
+    }
+}
@@ -0,0 +1,395 @@
+use super::{Object, Prefab, Scene, SceneFormat};
+use just_assets::{AssetState, AssetStorage};
+use just_wgpu::{Mesh, Texture};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathSegment {
+    Field(String),
+    Index(usize),
+}
+
+/// Location of a value inside scene file, displayed as `objects[3].renderable.mesh`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValuePath(pub Vec<PathSegment>);
+
+impl ValuePath {
+    fn with_field(&self, name: &str) -> Self {
+        let mut path = self.clone();
+        path.0.push(PathSegment::Field(name.to_owned()));
+        path
+    }
+
+    fn with_index(&self, index: usize) -> Self {
+        let mut path = self.clone();
+        path.0.push(PathSegment::Index(index));
+        path
+    }
+
+    fn from_serde(path: &serde_path_to_error::Path) -> Self {
+        use serde_path_to_error::Segment;
+        Self(
+            path.iter()
+                .filter_map(|segment| match segment {
+                    Segment::Seq { index } => Some(PathSegment::Index(*index)),
+                    Segment::Map { key } | Segment::Enum { variant: key } => Some(PathSegment::Field(key.clone())),
+                    Segment::Unknown => None,
+                })
+                .collect(),
+        )
+    }
+}
+
+impl std::fmt::Display for ValuePath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, segment) in self.0.iter().enumerate() {
+            match segment {
+                PathSegment::Field(name) if i == 0 => write!(f, "{}", name)?,
+                PathSegment::Field(name) => write!(f, ".{}", name)?,
+                PathSegment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    pub file: String,
+    /// 1-based line and column, if value could be located in file.
+    pub position: Option<(usize, usize)>,
+    pub path: ValuePath,
+    pub message: String,
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.file)?;
+        if let Some((line, column)) = self.position {
+            write!(f, ":{}:{}", line, column)?;
+        }
+        if self.path.0.is_empty() {
+            write!(f, ": {}", self.message)
+        } else {
+            write!(f, ": {}: {}", self.path, self.message)
+        }
+    }
+}
+
+/// Asset storages names in scene files are checked against.
+pub struct References<'a> {
+    pub meshes: &'a AssetStorage<Mesh>,
+    pub textures: &'a AssetStorage<Texture>,
+    /// Checked only once all prefabs are loaded, see `SceneSystem::initialize`.
+    pub prefabs: Option<&'a AssetStorage<Prefab>>,
+}
+
+/// Deserializes scene or prefab file, reporting position and path of the first problem.
+pub fn parse_file<T: serde::de::DeserializeOwned>(file: &str, text: &str) -> Result<T, ValidationError> {
+    let (path, position, message) = match SceneFormat::detect(text) {
+        SceneFormat::Json => {
+            let mut deserializer = serde_json::Deserializer::from_str(text);
+            let (path, inner) = match serde_path_to_error::deserialize(&mut deserializer) {
+                Ok(value) => match deserializer.end() {
+                    Ok(()) => return Ok(value),
+                    Err(error) => (ValuePath::default(), error),
+                },
+                Err(error) => (ValuePath::from_serde(error.path()), error.into_inner()),
+            };
+            let position = if inner.line() > 0 {
+                Some((inner.line(), inner.column()))
+            } else {
+                None
+            };
+            // serde_json appends position to message, it is reported separately
+            let message = inner.to_string();
+            let suffix = format!(" at line {} column {}", inner.line(), inner.column());
+            let message = message.trim_end_matches(suffix.as_str()).to_owned();
+            (path, position, message)
+        }
+        SceneFormat::Ron => {
+            let mut deserializer = match ron::de::Deserializer::from_str(text) {
+                Ok(deserializer) => deserializer,
+                Err(error) => return Err(ron_error(file, ValuePath::default(), error)),
+            };
+            let error = match serde_path_to_error::deserialize(&mut deserializer) {
+                Ok(value) => match deserializer.end() {
+                    Ok(()) => return Ok(value),
+                    Err(error) => ron_error(file, ValuePath::default(), error),
+                },
+                Err(error) => ron_error(file, ValuePath::from_serde(error.path()), error.into_inner()),
+            };
+            (error.path, error.position, error.message)
+        }
+    };
+    let position = position.or_else(|| locate(text, &path));
+    Err(ValidationError {
+        file: file.to_owned(),
+        position,
+        path,
+        message,
+    })
+}
+
+fn ron_error(file: &str, path: ValuePath, error: ron::de::Error) -> ValidationError {
+    let (position, message) = match &error {
+        ron::de::Error::Parser(_, position) => {
+            let prefix = format!("{}: ", position);
+            let message = error.to_string();
+            (
+                Some((position.line, position.col)),
+                message.trim_start_matches(prefix.as_str()).to_owned(),
+            )
+        }
+        _ => (None, error.to_string()),
+    };
+    ValidationError {
+        file: file.to_owned(),
+        position,
+        path,
+        message,
+    }
+}
+
+/// Checks that every mesh, texture and prefab used in scene exists.
+pub fn check_scene(file: &str, text: &str, scene: &Scene, references: &References) -> Vec<ValidationError> {
+    let mut errors = vec![];
+    let objects = ValuePath::default().with_field("objects");
+    for (i, object) in scene.objects.iter().enumerate() {
+        check_object(object, &objects.with_index(i), references, &mut errors);
+    }
+    with_positions(file, text, errors)
+}
+
+pub fn check_prefab(file: &str, text: &str, root: &Object, references: &References) -> Vec<ValidationError> {
+    let mut errors = vec![];
+    check_object(root, &ValuePath::default(), references, &mut errors);
+    with_positions(file, text, errors)
+}
+
+fn check_object(object: &Object, path: &ValuePath, references: &References, errors: &mut Vec<(ValuePath, String)>) {
+    if let (Some(prefab), Some(prefabs)) = (&object.prefab, references.prefabs) {
+        if let Some(message) = missing_asset(prefabs, "prefab", prefab) {
+            errors.push((path.with_field("prefab"), message));
+        }
+    }
+    if let Some(renderable) = &object.renderable {
+        let path = path.with_field("renderable");
+        if let Some(message) = missing_asset(references.meshes, "mesh", &renderable.mesh) {
+            errors.push((path.with_field("mesh"), message));
+        }
+        if let Some(message) = missing_asset(references.textures, "texture", &renderable.texture) {
+            errors.push((path.with_field("texture"), message));
+        }
+    }
+    let children = path.with_field("children");
+    for (i, child) in object.children.iter().flatten().enumerate() {
+        check_object(child, &children.with_index(i), references, errors);
+    }
+}
+
+/// Assets that are registered but failed to load can't be used either, objects are created from their values.
+fn missing_asset<T>(storage: &AssetStorage<T>, kind: &str, name: &str) -> Option<String> {
+    let handle = match storage.get_handle(name) {
+        Some(handle) => handle,
+        None => return Some(format!("unknown {} '{}'", kind, name)),
+    };
+    match storage.get_state(&handle) {
+        Some(AssetState::Loaded(_)) => None,
+        _ => Some(format!("{} '{}' is not loaded", kind, name)),
+    }
+}
+
+fn with_positions(file: &str, text: &str, errors: Vec<(ValuePath, String)>) -> Vec<ValidationError> {
+    errors
+        .into_iter()
+        .map(|(path, message)| ValidationError {
+            file: file.to_owned(),
+            position: locate(text, &path),
+            path,
+            message,
+        })
+        .collect()
+}
+
+/// Finds line and column of value under given path.
+/// Understands both JSON and RON, as only structure of the file matters here.
+pub fn locate(text: &str, path: &ValuePath) -> Option<(usize, usize)> {
+    let mut cursor = Cursor {
+        text: text.as_bytes(),
+        pos: 0,
+    };
+    let offset = cursor.find(&path.0)?;
+    let before = &text[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+    Some((line, column))
+}
+
+struct Cursor<'a> {
+    text: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_ascii_whitespace() => self.pos += 1,
+                Some(b'/') if self.text.get(self.pos + 1) == Some(&b'/') => {
+                    while !matches!(self.peek(), None | Some(b'\n')) {
+                        self.pos += 1;
+                    }
+                }
+                Some(b'/') if self.text.get(self.pos + 1) == Some(&b'*') => {
+                    self.pos += 2;
+                    while self.peek().is_some() && !self.text[self.pos..].starts_with(b"*/") {
+                        self.pos += 1;
+                    }
+                    self.pos = (self.pos + 2).min(self.text.len());
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn is_identifier(c: u8) -> bool {
+        c.is_ascii_alphanumeric() || c == b'_' || c == b'.' || c == b'-' || c == b'+'
+    }
+
+    fn read_identifier(&mut self) -> &'a str {
+        let start = self.pos;
+        while self.peek().map(Self::is_identifier).unwrap_or(false) {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.text[start..self.pos]).unwrap_or("")
+    }
+
+    fn read_string(&mut self) -> &'a str {
+        // opening quote
+        self.pos += 1;
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            match c {
+                b'\\' => self.pos += 2,
+                b'"' => break,
+                _ => self.pos += 1,
+            }
+        }
+        let end = self.pos.min(self.text.len());
+        self.pos += 1;
+        std::str::from_utf8(&self.text[start..end]).unwrap_or("")
+    }
+
+    fn read_key(&mut self) -> &'a str {
+        match self.peek() {
+            Some(b'"') => self.read_string(),
+            _ => self.read_identifier(),
+        }
+    }
+
+    fn skip_value(&mut self) {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'"') => {
+                self.read_string();
+            }
+            Some(b'{') | Some(b'(') | Some(b'[') => {
+                let mut depth = 0;
+                while let Some(c) = self.peek() {
+                    match c {
+                        b'"' => {
+                            self.read_string();
+                            continue;
+                        }
+                        b'{' | b'(' | b'[' => depth += 1,
+                        b'}' | b')' | b']' => depth -= 1,
+                        _ => (),
+                    }
+                    self.pos += 1;
+                    if depth == 0 {
+                        return;
+                    }
+                }
+            }
+            Some(_) => {
+                self.read_identifier();
+                self.skip_whitespace();
+                // RON struct name or `Some` wrapper
+                if self.peek() == Some(b'(') {
+                    self.skip_value();
+                }
+            }
+            None => (),
+        }
+    }
+
+    fn skip_separator(&mut self) {
+        self.skip_whitespace();
+        if self.peek() == Some(b',') {
+            self.pos += 1;
+        }
+    }
+
+    fn find(&mut self, path: &[PathSegment]) -> Option<usize> {
+        self.skip_whitespace();
+        if path.is_empty() {
+            return Some(self.pos);
+        }
+        if self.peek().map(|c| c.is_ascii_alphabetic()).unwrap_or(false) {
+            // RON struct name or `Some` wrapper, value is inside of the parentheses
+            let identifier = self.read_identifier();
+            self.skip_whitespace();
+            if self.peek() != Some(b'(') {
+                return None;
+            }
+            if identifier == "Some" {
+                self.pos += 1;
+                return self.find(path);
+            }
+        }
+        match (self.peek()?, &path[0]) {
+            (b'{', PathSegment::Field(field)) | (b'(', PathSegment::Field(field)) => {
+                self.pos += 1;
+                loop {
+                    self.skip_whitespace();
+                    match self.peek()? {
+                        b'}' | b')' => return None,
+                        _ => (),
+                    }
+                    let key = self.read_key();
+                    self.skip_whitespace();
+                    if self.peek()? != b':' {
+                        return None;
+                    }
+                    self.pos += 1;
+                    if key == field {
+                        return self.find(&path[1..]);
+                    }
+                    self.skip_value();
+                    self.skip_separator();
+                }
+            }
+            (b'[', PathSegment::Index(index)) => {
+                self.pos += 1;
+                let mut current = 0;
+                loop {
+                    self.skip_whitespace();
+                    if self.peek()? == b']' {
+                        return None;
+                    }
+                    if current == *index {
+                        return self.find(&path[1..]);
+                    }
+                    self.skip_value();
+                    self.skip_separator();
+                    current += 1;
+                }
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use boa_engine::{Context, JsArgs, JsNativeError, JsResult, JsValue, NativeFunction};
+use just_core::math::{look_rotation, Quat, Vec3};
+use just_wgpu::{CameraData, ScreenData};
+
+use super::typings::GlobalObject;
+use super::{math, with_world};
+
+/// Registers global `Camera` object with `position`, `rotation`, `fovY`, `zNear`, `zFar` and
+/// read only `aspectRatio` properties. Changes are visible in the frame in which they were made.
+pub fn register(context: &mut Context) {
+    let number = |field: fn(&mut CameraData) -> &mut f32, check: fn(&CameraData, f32) -> Result<(), String>| {
+        let get = NativeFunction::from_copy_closure(move |_this, _args, _context| {
+            Ok(with_camera(|camera| *field(camera))?.into())
+        });
+        let set = NativeFunction::from_copy_closure(move |_this, args, context| {
+            let value = args.get_or_undefined(0).to_number(context)? as f32;
+            with_camera(|camera| -> Result<(), String> {
+                check(camera, value)?;
+                *field(camera) = value;
+                Ok(())
+            })?
+            .map_err(|message| JsNativeError::range().with_message(message))?;
+            Ok(JsValue::undefined())
+        });
+        (get, Some(set))
+    };
+    let (fov_get, fov_set) = number(
+        |camera| &mut camera.fov_y,
+        |_, fov| {
+            if fov > 0.0 && fov < std::f32::consts::PI {
+                Ok(())
+            } else {
+                Err(format!("fovY must be between 0 and PI, got {}", fov))
+            }
+        },
+    );
+    let (near_get, near_set) = number(
+        |camera| &mut camera.z_near,
+        |camera, near| {
+            if near > 0.0 && near < camera.z_far {
+                Ok(())
+            } else {
+                Err(format!("zNear must be positive and less than zFar, got {}", near))
+            }
+        },
+    );
+    let (far_get, far_set) = number(
+        |camera| &mut camera.z_far,
+        |camera, far| {
+            if far > camera.z_near {
+                Ok(())
+            } else {
+                Err(format!("zFar must be greater than zNear, got {}", far))
+            }
+        },
+    );
+    let get = NativeFunction::from_fn_ptr;
+    let set = |f: fn(&JsValue, &[JsValue], &mut Context) -> JsResult<JsValue>| Some(NativeFunction::from_fn_ptr(f));
+
+    GlobalObject::new("Camera")
+        .accessor("position", get(get_position), set(set_position), "Vector3")
+        .accessor("rotation", get(get_rotation), set(set_rotation), "Quat")
+        .accessor("fovY", fov_get, fov_set, "number")
+        .accessor("zNear", near_get, near_set, "number")
+        .accessor("zFar", far_get, far_set, "number")
+        .accessor("aspectRatio", get(get_aspect_ratio), None, "number")
+        .function(
+            "lookAt",
+            NativeFunction::from_fn_ptr(look_at),
+            "(target: Vector3, up?: Vector3): void",
+        )
+        .register(context);
+}
+
+fn with_camera<R, F: FnOnce(&mut CameraData) -> R>(f: F) -> JsResult<R> {
+    with_world(|world| f(&mut world.resources.get_mut::<ScreenData>().unwrap().camera))
+}
+
+fn get_position(_this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let position = with_camera(|camera| camera.position)?;
+    Ok(math::to_js(context, position))
+}
+
+pub fn set_position(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let position = math::from_js::<Vec3>(args.get_or_undefined(0), context)?;
+    with_camera(|camera| camera.position = position)?;
+    Ok(JsValue::undefined())
+}
+
+fn get_rotation(_this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let rotation = with_camera(|camera| camera.rotation)?;
+    Ok(math::to_js(context, rotation))
+}
+
+pub fn set_rotation(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let rotation = math::from_js::<Quat>(args.get_or_undefined(0), context)?;
+    with_camera(|camera| camera.rotation = rotation.normalize())?;
+    Ok(JsValue::undefined())
+}
+
+fn get_aspect_ratio(_this: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+    Ok(with_camera(|camera| camera.aspect_ratio)?.into())
+}
+
+/// `Camera.lookAt(target, up?)` rotates camera towards `target`, `up` defaults to Y axis.
+fn look_at(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let target = math::from_js::<Vec3>(args.get_or_undefined(0), context)?;
+    let up = match args.get(1) {
+        Some(up) if !up.is_undefined() => math::from_js::<Vec3>(up, context)?,
+        _ => Vec3::Y,
+    };
+    with_camera(|camera| {
+        // same as camera following player in game logic, camera keeps inverse of its orientation
+        camera.rotation = look_rotation(target - camera.position, up).inverse();
+    })?;
+    Ok(JsValue::undefined())
+}
@@ -0,0 +1,148 @@
+use boa_engine::{js_string, Context, JsData, JsError, JsObject, JsResult, JsValue, NativeFunction};
+use boa_gc::{Finalize, Trace};
+use just_core::ecs::prelude::*;
+use just_wgpu::{Console, ConsoleEntry, LogLevel};
+use std::collections::HashMap;
+
+use super::reload;
+use super::typings::GlobalObject;
+use super::with_world;
+
+/// Source of `console` calls, e.g. controller whose hook is running. Stored in JS realm.
+#[derive(Default, Trace, Finalize, JsData)]
+struct CurrentSource(Option<String>);
+
+pub fn register(context: &mut Context) {
+    context.realm().host_defined_mut().insert(CurrentSource::default());
+
+    GlobalObject::new("console")
+        .function("log", NativeFunction::from_fn_ptr(log), "(...args: any[]): void")
+        .function("error", NativeFunction::from_fn_ptr(error), "(...args: any[]): void")
+        .register(context);
+}
+
+/// Sets source given to entries logged by scripts until next call.
+pub fn set_source(context: &mut Context, source: Option<String>) {
+    let realm = context.realm().clone();
+    let mut host_defined = realm.host_defined_mut();
+    host_defined.get_mut::<CurrentSource>().unwrap().0 = source;
+}
+
+fn current_source(context: &Context) -> Option<String> {
+    let host_defined = context.realm().host_defined();
+    host_defined.get::<CurrentSource>().unwrap().0.clone()
+}
+
+/// Prints entry and adds it to in-game console, if there is one.
+pub fn report(world: &mut World, entry: ConsoleEntry) {
+    let source = entry.source.as_ref().map(|s| format!("[{}] ", s)).unwrap_or_default();
+    let location = entry
+        .location
+        .as_ref()
+        .map(|l| format!(" at {}", l))
+        .unwrap_or_default();
+    match entry.level {
+        LogLevel::Info => println!("{}{}", source, entry.message),
+        LogLevel::Error => {
+            println!("error: {}{}{}", source, entry.message, location);
+            for frame in entry.stack.iter() {
+                println!("    {}", frame);
+            }
+        }
+    }
+    if let Some(mut console) = world.resources.get_mut::<Console>() {
+        console.log(entry);
+    }
+}
+
+/// Arguments are joined without separators, strings are printed without quotes.
+pub fn format_args(args: &[JsValue]) -> String {
+    args.iter()
+        .map(|arg| match arg.as_string() {
+            Some(text) => text.to_std_string_escaped(),
+            None => arg.display().to_string(),
+        })
+        .collect()
+}
+
+fn log(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let entry = ConsoleEntry::info(current_source(context), format_args(args));
+    with_world(|world| report(world, entry))?;
+    Ok(JsValue::undefined())
+}
+
+fn error(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let entry = ConsoleEntry::error(current_source(context), format_args(args));
+    with_world(|world| report(world, entry))?;
+    Ok(JsValue::undefined())
+}
+
+/// Text of loaded scripts by file name, used to point errors at source.
+#[derive(Default)]
+pub struct ScriptSources {
+    files: HashMap<String, String>,
+}
+
+impl ScriptSources {
+    pub fn insert(&mut self, file: String, source: String) {
+        self.files.insert(file, source);
+    }
+
+    /// `file:line:column` of class declaration, or of its method if `method` is given and found.
+    pub fn locate(&self, class: &str, method: Option<&str>) -> Option<String> {
+        let mut files: Vec<(&String, &String)> = self.files.iter().collect();
+        // later files (entry script is the last one) shadow classes of earlier ones
+        files.sort();
+        files.into_iter().rev().find_map(|(file, source)| {
+            let declaration = reload::class_declarations(source)
+                .into_iter()
+                .find(|declaration| declaration.name == class)?;
+            let offset = method
+                .and_then(|method| reload::method_offset(&declaration.text, method))
+                .unwrap_or(0);
+            let (line, column) = reload::line_column(source, declaration.start + offset);
+            Some(format!("{}:{}:{}", file, line, column))
+        })
+    }
+}
+
+/// Name of class object was constructed with.
+pub fn class_name(context: &mut Context, object: &JsObject) -> Option<String> {
+    let constructor = object.get(js_string!("constructor"), context).ok()?;
+    let name = constructor.as_object()?.get(js_string!("name"), context).ok()?;
+    name.as_string().map(|name| name.to_std_string_escaped())
+}
+
+/// Error entry for exception thrown by script.
+/// Boa doesn't record where exceptions were thrown, so for runtime errors `frame`, the function
+/// that was called by engine, is the best known location. Syntax errors carry their own position.
+pub fn error_entry(
+    error: &JsError,
+    source: Option<String>,
+    file: Option<&str>,
+    frame: Option<(String, Option<String>)>,
+) -> ConsoleEntry {
+    let message = error.to_string();
+    let mut entry = ConsoleEntry::error(source, message.clone());
+    entry.location = match (file, syntax_error_position(&message)) {
+        (Some(file), Some((line, column))) => Some(format!("{}:{}:{}", file, line, column)),
+        (Some(file), None) => Some(file.to_owned()),
+        (None, _) => frame.as_ref().and_then(|(_, location)| location.clone()),
+    };
+    if let Some((function, location)) = frame {
+        entry.stack.push(match location {
+            Some(location) => format!("at {} ({})", function, location),
+            None => format!("at {}", function),
+        });
+    }
+    entry
+}
+
+/// Line and column from parser messages like "... at line 3, col 14".
+fn syntax_error_position(message: &str) -> Option<(u32, u32)> {
+    let position = &message[message.rfind("at line ")? + "at line ".len()..];
+    let mut numbers = position.split(", col ");
+    let line = numbers.next()?.trim().parse().ok()?;
+    let column: String = numbers.next()?.chars().take_while(|c| c.is_ascii_digit()).collect();
+    Some((line, column.parse().ok()?))
+}
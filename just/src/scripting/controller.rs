@@ -0,0 +1,194 @@
+use boa_engine::{js_string, Context, JsData, JsObject, JsResult};
+use boa_gc::{Finalize, Trace};
+use just_core::ecs::prelude::*;
+use std::collections::HashMap;
+
+use super::game_object::GameObjectHandle;
+use super::limits;
+use super::typings::{self, Interface, Placement};
+
+/// Method of controller called by engine, controllers don't have to implement all of them.
+pub struct Hook {
+    pub name: &'static str,
+    /// TypeScript signature.
+    pub signature: &'static str,
+}
+
+impl Hook {
+    pub const START: Hook = Hook {
+        name: "start",
+        signature: "(): void",
+    };
+    pub const UPDATE: Hook = Hook {
+        name: "update",
+        signature: "(dt: number): void",
+    };
+    pub const ON_PARENT_CHANGED: Hook = Hook {
+        name: "onParentChanged",
+        signature: "(newParent: GameObject | null, oldParent: GameObject | null): void",
+    };
+    pub const ON_DESTROY: Hook = Hook {
+        name: "onDestroy",
+        signature: "(): void",
+    };
+    pub const ALL: &'static [Hook] = &[Hook::START, Hook::UPDATE, Hook::ON_PARENT_CHANGED, Hook::ON_DESTROY];
+}
+
+/// Links game object with JS object controlling it.
+/// Controller objects live in `Controllers`, stored in JS realm so that native bindings can attach them too.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScriptController {
+    pub id: u32,
+}
+
+#[derive(Trace, Finalize)]
+pub struct Controller {
+    pub object: JsObject,
+    #[unsafe_ignore_trace]
+    pub entity: Entity,
+    /// `start()` was already called.
+    pub started: bool,
+    /// Why controller broke `ScriptLimits`, disabled controllers get no calls.
+    pub disabled: Option<String>,
+}
+
+#[derive(Default, Trace, Finalize, JsData)]
+pub struct Controllers {
+    last_id: u32,
+    controllers: HashMap<u32, Controller>,
+}
+
+impl Controllers {
+    /// Also declares `Controller` interface, which script classes can implement.
+    pub fn install(context: &mut Context) {
+        context.realm().host_defined_mut().insert(Controllers::default());
+
+        let mut declaration = Interface::new("Controller", Placement::None).property("go", "GameObject", false);
+        for hook in Hook::ALL {
+            declaration = declaration.optional_method(hook.name, hook.signature);
+        }
+        typings::declare(context, declaration);
+    }
+
+    /// Registers `object` as controller of `entity` and gives it `go` handle.
+    /// Caller is responsible for adding returned `ScriptController` to the entity.
+    pub fn register(context: &mut Context, entity: Entity, object: JsObject) -> JsResult<ScriptController> {
+        let handle = GameObjectHandle::new_object(context, entity);
+        object.set(js_string!("go"), handle, true, context)?;
+
+        let realm = context.realm().clone();
+        let mut host_defined = realm.host_defined_mut();
+        let controllers = host_defined.get_mut::<Controllers>().unwrap();
+        controllers.last_id += 1;
+        let id = controllers.last_id;
+        controllers.controllers.insert(
+            id,
+            Controller {
+                object,
+                entity,
+                started: false,
+                disabled: None,
+            },
+        );
+        limits::track_owner(id);
+        Ok(ScriptController { id })
+    }
+
+    pub fn remove(context: &mut Context, id: u32) -> Option<Controller> {
+        let realm = context.realm().clone();
+        let mut host_defined = realm.host_defined_mut();
+        limits::forget_owner(id);
+        host_defined.get_mut::<Controllers>().unwrap().controllers.remove(&id)
+    }
+
+    /// Marks controller as started, returns whether it wasn't started before.
+    pub fn start(context: &mut Context, id: u32) -> bool {
+        let realm = context.realm().clone();
+        let mut host_defined = realm.host_defined_mut();
+        match host_defined.get_mut::<Controllers>().unwrap().controllers.get_mut(&id) {
+            Some(controller) => !std::mem::replace(&mut controller.started, true),
+            None => false,
+        }
+    }
+
+    /// Controller object, `None` if it was removed or disabled.
+    pub fn get(context: &Context, id: u32) -> Option<JsObject> {
+        let host_defined = context.realm().host_defined();
+        host_defined
+            .get::<Controllers>()
+            .unwrap()
+            .controllers
+            .get(&id)
+            .filter(|controller| controller.disabled.is_none())
+            .map(|controller| controller.object.clone())
+    }
+
+    /// Entity controller is attached to, disabled controllers included.
+    pub fn entity(context: &Context, id: u32) -> Option<Entity> {
+        let host_defined = context.realm().host_defined();
+        let controllers = host_defined.get::<Controllers>().unwrap();
+        controllers.controllers.get(&id).map(|controller| controller.entity)
+    }
+
+    /// Objects of all controllers, disabled ones included.
+    pub fn objects(context: &Context) -> Vec<(u32, JsObject)> {
+        let host_defined = context.realm().host_defined();
+        host_defined
+            .get::<Controllers>()
+            .unwrap()
+            .controllers
+            .iter()
+            .map(|(id, controller)| (*id, controller.object.clone()))
+            .collect()
+    }
+
+    /// Returns entity of disabled controller.
+    pub fn disable(context: &mut Context, id: u32, reason: String) -> Option<Entity> {
+        let realm = context.realm().clone();
+        let mut host_defined = realm.host_defined_mut();
+        let controller = host_defined
+            .get_mut::<Controllers>()
+            .unwrap()
+            .controllers
+            .get_mut(&id)?;
+        controller.disabled = Some(reason);
+        Some(controller.entity)
+    }
+
+    /// Returns whether controller was disabled.
+    pub fn enable(context: &mut Context, id: u32) -> bool {
+        let realm = context.realm().clone();
+        let mut host_defined = realm.host_defined_mut();
+        match host_defined.get_mut::<Controllers>().unwrap().controllers.get_mut(&id) {
+            Some(controller) => controller.disabled.take().is_some(),
+            None => false,
+        }
+    }
+
+    /// Disabled controllers with their entities and reasons.
+    pub fn disabled(context: &Context) -> Vec<(u32, Entity, String)> {
+        let host_defined = context.realm().host_defined();
+        host_defined
+            .get::<Controllers>()
+            .unwrap()
+            .controllers
+            .iter()
+            .filter_map(|(id, c)| Some((*id, c.entity, c.disabled.clone()?)))
+            .collect()
+    }
+
+    /// Ids of all controllers with entities they are attached to.
+    pub fn all(context: &Context) -> Vec<(u32, Entity)> {
+        let host_defined = context.realm().host_defined();
+        let mut all: Vec<(u32, Entity)> = host_defined
+            .get::<Controllers>()
+            .unwrap()
+            .controllers
+            .iter()
+            .map(|(id, controller)| (*id, controller.entity))
+            .collect();
+        // keep update order stable between frames
+        all.sort_by_key(|(id, _)| *id);
+        all
+    }
+}
@@ -0,0 +1,201 @@
+use boa_engine::object::builtins::JsArray;
+use boa_engine::{js_string, Context, JsArgs, JsData, JsNativeError, JsObject, JsResult, JsValue, NativeFunction};
+use boa_gc::{Finalize, Trace};
+use just_core::ecs::prelude::*;
+use just_core::events::{EventBus, EventError, EventPayload, EventReader};
+
+use super::typings::GlobalObject;
+use super::{console, timers, with_world};
+
+#[derive(Trace, Finalize)]
+struct Subscription {
+    id: u32,
+    callback: JsObject,
+    #[unsafe_ignore_trace]
+    reader: EventReader,
+    /// Game object whose controller subscribed, subscription is cancelled when it's destroyed.
+    #[unsafe_ignore_trace]
+    owner: Option<Entity>,
+}
+
+/// Callbacks registered by scripts, each one has its own reader. Stored in JS realm.
+#[derive(Default, Trace, Finalize, JsData)]
+struct Subscriptions {
+    last_id: u32,
+    subscriptions: Vec<Subscription>,
+}
+
+/// Registers global `Events` object, scripts' side of `EventBus`:
+/// - `Events.declare(name)` declares event with any payload,
+/// - `Events.publish(name, payload)` sends JSON-like payload, events declared by Rust check its shape,
+/// - `Events.on(name, callback)` returns id to be passed to `Events.off`,
+/// - `Events.names()` lists declared events.
+/// Callbacks get events published since previous frame, before controllers are updated.
+/// Subscriptions made by controllers are cancelled together with their game object.
+pub fn register(context: &mut Context) {
+    context.realm().host_defined_mut().insert(Subscriptions::default());
+
+    GlobalObject::new("Events")
+        .function("declare", NativeFunction::from_fn_ptr(declare), "(name: string): void")
+        .function(
+            "publish",
+            NativeFunction::from_fn_ptr(publish),
+            "(name: string, payload?: any): void",
+        )
+        .function(
+            "on",
+            NativeFunction::from_fn_ptr(on),
+            "(name: string, callback: (payload: any) => void): number",
+        )
+        .function("off", NativeFunction::from_fn_ptr(off), "(id: number): boolean")
+        .function("names", NativeFunction::from_fn_ptr(names), "(): string[]")
+        .register(context);
+}
+
+pub fn cancel_owned(context: &mut Context, owner: Entity) {
+    let realm = context.realm().clone();
+    let mut host_defined = realm.host_defined_mut();
+    let subscriptions = host_defined.get_mut::<Subscriptions>().unwrap();
+    subscriptions
+        .subscriptions
+        .retain(|subscription| subscription.owner != Some(owner));
+}
+
+/// Distinct owners of subscriptions.
+pub fn owners(context: &Context) -> Vec<Entity> {
+    let host_defined = context.realm().host_defined();
+    let mut owners: Vec<Entity> = vec![];
+    for owner in host_defined
+        .get::<Subscriptions>()
+        .unwrap()
+        .subscriptions
+        .iter()
+        .filter_map(|subscription| subscription.owner)
+    {
+        if !owners.contains(&owner) {
+            owners.push(owner);
+        }
+    }
+    owners
+}
+
+/// Calls callbacks with events published since last call. Timers and subscriptions created by callback
+/// belong to the same object as the subscription.
+pub fn dispatch(context: &mut Context) {
+    let ids: Vec<u32> = {
+        let host_defined = context.realm().host_defined();
+        let subscriptions = host_defined.get::<Subscriptions>().unwrap();
+        subscriptions
+            .subscriptions
+            .iter()
+            .map(|subscription| subscription.id)
+            .collect()
+    };
+    for id in ids {
+        let (callback, owner, payloads) = {
+            let realm = context.realm().clone();
+            let mut host_defined = realm.host_defined_mut();
+            let subscriptions = host_defined.get_mut::<Subscriptions>().unwrap();
+            // might have been removed by earlier callback
+            let subscription = match subscriptions.subscriptions.iter_mut().find(|s| s.id == id) {
+                Some(subscription) => subscription,
+                None => continue,
+            };
+            let reader = &mut subscription.reader;
+            let payloads = with_world(|world| {
+                let bus = world.resources.get::<EventBus>().unwrap();
+                let payloads: Vec<EventPayload> = bus.read(reader).cloned().collect();
+                payloads
+            });
+            match payloads {
+                Ok(payloads) => (subscription.callback.clone(), subscription.owner, payloads),
+                Err(_) => return,
+            }
+        };
+        let previous_owner = timers::set_owner(context, owner);
+        for payload in payloads {
+            let result = JsValue::from_json(&payload, context)
+                .and_then(|payload| callback.call(&JsValue::undefined(), &[payload], context));
+            if let Err(error) = result {
+                let entry = console::error_entry(&error, Some("Events".to_owned()), None, None);
+                let _ = with_world(|world| console::report(world, entry));
+            }
+        }
+        timers::set_owner(context, previous_owner);
+    }
+}
+
+fn name_arg(args: &[JsValue], context: &mut Context) -> JsResult<String> {
+    Ok(args.get_or_undefined(0).to_string(context)?.to_std_string_escaped())
+}
+
+fn event_error(error: EventError) -> boa_engine::JsError {
+    match error {
+        EventError::Undeclared(_) => JsNativeError::reference().with_message(error.to_string()).into(),
+        EventError::InvalidPayload { .. } => JsNativeError::typ().with_message(error.to_string()).into(),
+    }
+}
+
+fn declare(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = name_arg(args, context)?;
+    with_world(|world| world.resources.get_mut::<EventBus>().unwrap().declare(&name))?;
+    Ok(JsValue::undefined())
+}
+
+/// `undefined` payload is sent as `null`, functions and symbols can't be sent.
+fn publish(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = name_arg(args, context)?;
+    let payload = match args.get_or_undefined(1) {
+        payload if payload.is_undefined() => EventPayload::Null,
+        payload => payload.to_json(context)?,
+    };
+    with_world(|world| world.resources.get_mut::<EventBus>().unwrap().publish(&name, payload))?.map_err(event_error)?;
+    Ok(JsValue::undefined())
+}
+
+fn on(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = name_arg(args, context)?;
+    let callback = args
+        .get_or_undefined(1)
+        .as_callable()
+        .cloned()
+        .ok_or_else(|| JsNativeError::typ().with_message("callback is not a function"))?;
+    let reader = with_world(|world| world.resources.get_mut::<EventBus>().unwrap().register_reader(&name))?
+        .map_err(event_error)?;
+
+    let owner = timers::owner(context);
+    let realm = context.realm().clone();
+    let mut host_defined = realm.host_defined_mut();
+    let subscriptions = host_defined.get_mut::<Subscriptions>().unwrap();
+    subscriptions.last_id += 1;
+    let id = subscriptions.last_id;
+    subscriptions.subscriptions.push(Subscription {
+        id,
+        callback,
+        reader,
+        owner,
+    });
+    Ok(id.into())
+}
+
+fn off(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let id = args.get_or_undefined(0).to_number(context)?;
+    let realm = context.realm().clone();
+    let mut host_defined = realm.host_defined_mut();
+    let subscriptions = host_defined.get_mut::<Subscriptions>().unwrap();
+    let count = subscriptions.subscriptions.len();
+    subscriptions
+        .subscriptions
+        .retain(|subscription| subscription.id as f64 != id);
+    Ok((subscriptions.subscriptions.len() != count).into())
+}
+
+fn names(_this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let names: Vec<String> = with_world(|world| {
+        let bus = world.resources.get::<EventBus>().unwrap();
+        let names = bus.names().into_iter().map(str::to_owned).collect();
+        names
+    })?;
+    let names = names.into_iter().map(|name| js_string!(name).into());
+    Ok(JsArray::from_iter(names, context).into())
+}
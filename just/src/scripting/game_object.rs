@@ -0,0 +1,354 @@
+use boa_engine::class::{Class, ClassBuilder};
+use boa_engine::object::builtins::JsArray;
+use boa_engine::{js_string, Context, JsArgs, JsData, JsNativeError, JsObject, JsResult, JsValue, NativeFunction};
+use boa_gc::{Finalize, Trace};
+use just_core::commands::Commands;
+use just_core::ecs::prelude::*;
+use just_core::game_object::GameObject;
+use just_core::hierarchy::{HierarchyError, TransformHierarchy};
+use just_core::math::{Quat, Vec3};
+use just_wgpu::{Renderable, RenderableError, RenderingSystem};
+
+use super::math;
+use super::resources::{AssetHandle, AssetKind};
+use super::typings::DeclaredClass;
+use super::with_world;
+
+/// Handle to game object given to scripts. Holds only the id, so it stays valid (but unusable)
+/// after object is destroyed.
+#[derive(Debug, Clone, Copy, Trace, Finalize, JsData)]
+#[boa_gc(empty_trace)]
+pub struct GameObjectHandle {
+    pub id: Entity,
+}
+
+impl GameObjectHandle {
+    pub fn new_object(context: &mut Context, id: Entity) -> JsObject {
+        Self::from_data(GameObjectHandle { id }, context).unwrap()
+    }
+
+    /// Id stored in handle, doesn't check whether object still exists.
+    pub fn id_of(value: &JsValue) -> Option<Entity> {
+        value
+            .as_object()
+            .and_then(|object| object.downcast_ref::<GameObjectHandle>().map(|handle| handle.id))
+    }
+
+    /// Id of game object behind `value`, fails for anything else and for destroyed objects.
+    pub fn alive_id(value: &JsValue) -> JsResult<Entity> {
+        let id = Self::id_of(value).ok_or_else(|| JsNativeError::typ().with_message("expected GameObject"))?;
+        if with_world(|world| world.is_alive(id))? {
+            Ok(id)
+        } else {
+            Err(JsNativeError::error().with_message("game object was destroyed").into())
+        }
+    }
+
+    fn get_name(this: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        let name = with_world(|world| GameObject::get_name(world, id))?.map_err(hierarchy_error)?;
+        Ok(js_string!(name).into())
+    }
+
+    fn set_name(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+        with_world(|world| GameObject::set_name(world, id, name))?.map_err(hierarchy_error)?;
+        Ok(JsValue::undefined())
+    }
+
+    fn get_tags(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        let tags = with_world(|world| GameObject::get_tags(world, id))?.map_err(hierarchy_error)?;
+        let tags = tags.into_iter().map(|tag| js_string!(tag).into());
+        Ok(JsArray::from_iter(tags, context).into())
+    }
+
+    fn add_tag(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        let tag = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+        with_world(|world| GameObject::add_tag(world, id, &tag))?.map_err(hierarchy_error)?;
+        Ok(JsValue::undefined())
+    }
+
+    fn remove_tag(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        let tag = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+        let removed = with_world(|world| GameObject::remove_tag(world, id, &tag))?.map_err(hierarchy_error)?;
+        Ok(removed.into())
+    }
+
+    fn has_tag(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        let tag = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+        let has = with_world(|world| GameObject::has_tag(world, id, &tag))?.map_err(hierarchy_error)?;
+        Ok(has.into())
+    }
+
+    fn get_layer_mask(this: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        let mask = with_world(|world| GameObject::get_layer_mask(world, id))?.map_err(hierarchy_error)?;
+        Ok(mask.into())
+    }
+
+    fn set_layer_mask(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        let mask = args.get_or_undefined(0).to_u32(context)?;
+        with_world(|world| GameObject::set_layer_mask(world, id, mask))?.map_err(hierarchy_error)?;
+        Ok(JsValue::undefined())
+    }
+
+    fn get_active(this: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        let active = with_world(|world| GameObject::is_active_self(world, id))?.map_err(hierarchy_error)?;
+        Ok(active.into())
+    }
+
+    /// Inactive objects aren't rendered and their controllers aren't updated, same goes for their descendants.
+    fn set_active(this: &JsValue, args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        let active = args.get_or_undefined(0).to_boolean();
+        with_world(|world| GameObject::set_active(world, id, active))?.map_err(hierarchy_error)?;
+        Ok(JsValue::undefined())
+    }
+
+    fn get_active_in_hierarchy(this: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        let active = with_world(|world| GameObject::is_active_in_hierarchy(world, id))?.map_err(hierarchy_error)?;
+        Ok(active.into())
+    }
+
+    fn get_position(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        let position =
+            with_world(|world| TransformHierarchy::get_local_position(world, id))?.map_err(hierarchy_error)?;
+        Ok(math::to_js(context, position))
+    }
+
+    fn set_position(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        let position = math::from_js::<Vec3>(args.get_or_undefined(0), context)?;
+        with_world(|world| TransformHierarchy::set_local_position(world, id, position))?.map_err(hierarchy_error)?;
+        Ok(JsValue::undefined())
+    }
+
+    fn get_rotation(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        let rotation =
+            with_world(|world| TransformHierarchy::get_local_rotation(world, id))?.map_err(hierarchy_error)?;
+        Ok(math::to_js(context, rotation))
+    }
+
+    fn set_rotation(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        let rotation = math::from_js::<Quat>(args.get_or_undefined(0), context)?;
+        with_world(|world| TransformHierarchy::set_local_rotation(world, id, rotation))?.map_err(hierarchy_error)?;
+        Ok(JsValue::undefined())
+    }
+
+    fn get_scale(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        let scale = with_world(|world| TransformHierarchy::get_local_scale(world, id))?.map_err(hierarchy_error)?;
+        Ok(math::to_js(context, scale))
+    }
+
+    fn set_scale(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        let scale = math::from_js::<Vec3>(args.get_or_undefined(0), context)?;
+        with_world(|world| TransformHierarchy::set_local_scale(world, id, scale))?.map_err(hierarchy_error)?;
+        Ok(JsValue::undefined())
+    }
+
+    fn get_global_position(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        let position =
+            with_world(|world| TransformHierarchy::get_global_position(world, id))?.map_err(hierarchy_error)?;
+        Ok(math::to_js(context, position))
+    }
+
+    fn get_parent(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        let parent = with_world(|world| TransformHierarchy::get_parent(world, id))?.map_err(hierarchy_error)?;
+        Ok(handle_or_null(context, parent))
+    }
+
+    fn get_children(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        let children = with_world(|world| world.get_component::<GameObject>(id).unwrap().children.clone())?;
+        let children = children
+            .into_iter()
+            .map(|child| GameObjectHandle::new_object(context, child).into())
+            .collect::<Vec<JsValue>>();
+        Ok(JsArray::from_iter(children, context).into())
+    }
+
+    /// `Asset` of object's mesh, `null` for objects that aren't rendered.
+    fn get_mesh(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        match with_world(|world| renderable_names(world, id))? {
+            Some((mesh, _)) => AssetHandle::resolve(context, AssetKind::Mesh, &mesh),
+            None => Ok(JsValue::null()),
+        }
+    }
+
+    /// Accepts mesh `Asset` or its name, mesh has to be loaded.
+    fn set_mesh(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        let name = AssetHandle::name_arg(args.get_or_undefined(0), AssetKind::Mesh, context)?;
+        with_world(|world| RenderingSystem::set_mesh(world, id, &name))?.map_err(renderable_error)?;
+        Ok(JsValue::undefined())
+    }
+
+    /// `Asset` of object's texture, `null` for objects that aren't rendered.
+    fn get_texture(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        match with_world(|world| renderable_names(world, id))? {
+            Some((_, texture)) => AssetHandle::resolve(context, AssetKind::Texture, &texture),
+            None => Ok(JsValue::null()),
+        }
+    }
+
+    /// Accepts texture `Asset` or its name, texture has to be loaded.
+    fn set_texture(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        let name = AssetHandle::name_arg(args.get_or_undefined(0), AssetKind::Texture, context)?;
+        with_world(|world| RenderingSystem::set_texture(world, id, &name))?.map_err(renderable_error)?;
+        Ok(JsValue::undefined())
+    }
+
+    /// `go.setParent(parent)`, `null` makes it a root object.
+    fn set_parent(this: &JsValue, args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        set_parent(id, args.get_or_undefined(0))?;
+        Ok(JsValue::undefined())
+    }
+
+    /// Object and its children are removed at the end of frame.
+    fn destroy(this: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+        let id = Self::alive_id(this)?;
+        with_world(|world| GameObject::delete(world, id))?;
+        Ok(JsValue::undefined())
+    }
+
+    fn is_alive(this: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+        let id = match Self::id_of(this) {
+            Some(id) => id,
+            None => return Ok(false.into()),
+        };
+        Ok(with_world(|world| world.is_alive(id))?.into())
+    }
+}
+
+impl Class for GameObjectHandle {
+    const NAME: &'static str = "GameObject";
+
+    fn init(class: &mut ClassBuilder<'_>) -> JsResult<()> {
+        let get = NativeFunction::from_fn_ptr;
+        let set = |f: fn(&JsValue, &[JsValue], &mut Context) -> JsResult<JsValue>| Some(NativeFunction::from_fn_ptr(f));
+        let mut class = DeclaredClass::new(class, Self::NAME, None);
+        class
+            .accessor("name", get(Self::get_name), set(Self::set_name), "string")
+            .accessor("active", get(Self::get_active), set(Self::set_active), "boolean")
+            .accessor("activeInHierarchy", get(Self::get_active_in_hierarchy), None, "boolean")
+            .accessor("tags", get(Self::get_tags), None, "string[]")
+            .accessor(
+                "layerMask",
+                get(Self::get_layer_mask),
+                set(Self::set_layer_mask),
+                "number",
+            )
+            .accessor("position", get(Self::get_position), set(Self::set_position), "Vector3")
+            .accessor("rotation", get(Self::get_rotation), set(Self::set_rotation), "Quat")
+            .accessor("scale", get(Self::get_scale), set(Self::set_scale), "Vector3")
+            .accessor("globalPosition", get(Self::get_global_position), None, "Vector3")
+            .accessor("parent", get(Self::get_parent), None, "GameObject | null")
+            .accessor("children", get(Self::get_children), None, "GameObject[]")
+            .accessor_types(
+                "mesh",
+                get(Self::get_mesh),
+                set(Self::set_mesh),
+                "Asset | null",
+                Some("Asset | string"),
+            )
+            .accessor_types(
+                "texture",
+                get(Self::get_texture),
+                set(Self::set_texture),
+                "Asset | null",
+                Some("Asset | string"),
+            )
+            .method(
+                "setParent",
+                NativeFunction::from_fn_ptr(Self::set_parent),
+                "(parent: GameObject | null): void",
+            )
+            .method(
+                "addTag",
+                NativeFunction::from_fn_ptr(Self::add_tag),
+                "(tag: string): void",
+            )
+            .method(
+                "removeTag",
+                NativeFunction::from_fn_ptr(Self::remove_tag),
+                "(tag: string): boolean",
+            )
+            .method(
+                "hasTag",
+                NativeFunction::from_fn_ptr(Self::has_tag),
+                "(tag: string): boolean",
+            )
+            .method("destroy", NativeFunction::from_fn_ptr(Self::destroy), "(): void")
+            .method("isAlive", NativeFunction::from_fn_ptr(Self::is_alive), "(): boolean");
+        class.finish();
+        Ok(())
+    }
+
+    fn data_constructor(_new_target: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<Self> {
+        Err(JsNativeError::typ()
+            .with_message("GameObject can't be constructed directly")
+            .into())
+    }
+}
+
+/// Reparents object, `parent` is a `GameObject`, `null` or `undefined`.
+pub fn set_parent(id: Entity, parent: &JsValue) -> JsResult<()> {
+    let parent = if parent.is_null_or_undefined() {
+        None
+    } else {
+        Some(GameObjectHandle::alive_id(parent)?)
+    };
+    with_world(|world| TransformHierarchy::set_parent(world, id, parent))?.map_err(hierarchy_error)
+}
+
+pub fn handle_or_null(context: &mut Context, id: Option<Entity>) -> JsValue {
+    match id {
+        Some(id) => GameObjectHandle::new_object(context, id).into(),
+        None => JsValue::null(),
+    }
+}
+
+/// Mesh and texture names of object's renderable, also when it's still waiting to be created.
+fn renderable_names(world: &World, id: Entity) -> Option<(String, String)> {
+    if let Some(renderable) = world.get_component::<Renderable>(id) {
+        return Some((renderable.mesh_name().to_owned(), renderable.texture_name().to_owned()));
+    }
+    let commands = world.resources.get::<Commands>().unwrap();
+    commands
+        .queued_renderable(id)
+        .map(|(mesh, texture)| (mesh.to_owned(), texture.to_owned()))
+}
+
+pub fn hierarchy_error(error: HierarchyError) -> boa_engine::JsError {
+    match error {
+        HierarchyError::DeadEntity(_) => JsNativeError::error().with_message("game object was destroyed").into(),
+        HierarchyError::MissingComponent { .. } => JsNativeError::typ().with_message(error.to_string()).into(),
+        _ => JsNativeError::error().with_message(error.to_string()).into(),
+    }
+}
+
+fn renderable_error(error: RenderableError) -> boa_engine::JsError {
+    match error {
+        RenderableError::NoRenderable => JsNativeError::typ().with_message(error.to_string()).into(),
+        _ => JsNativeError::error().with_message(error.to_string()).into(),
+    }
+}
@@ -0,0 +1,260 @@
+use boa_engine::{js_string, Context, JsArgs, JsData, JsNativeError, JsObject, JsResult, JsValue, NativeFunction};
+use boa_gc::{Finalize, Trace};
+use just_core::math::Vec2;
+use just_input::{InputEvent, KeyCode, KeyboardState, MouseState};
+
+use super::typings::{self, GlobalObject};
+use super::{console, math, with_world};
+
+const MOUSE_BUTTONS: usize = 8;
+
+/// Kinds of input events scripts can subscribe to with `Input.on(kind, callback)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EventKind {
+    KeyPressed,
+    KeyReleased,
+    MouseButtonPressed,
+    MouseButtonReleased,
+    MouseMoved,
+}
+
+impl EventKind {
+    const ALL: [EventKind; 5] = [
+        EventKind::KeyPressed,
+        EventKind::KeyReleased,
+        EventKind::MouseButtonPressed,
+        EventKind::MouseButtonReleased,
+        EventKind::MouseMoved,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            EventKind::KeyPressed => "keyPressed",
+            EventKind::KeyReleased => "keyReleased",
+            EventKind::MouseButtonPressed => "mouseButtonPressed",
+            EventKind::MouseButtonReleased => "mouseButtonReleased",
+            EventKind::MouseMoved => "mouseMoved",
+        }
+    }
+
+    /// TypeScript type of value passed to callbacks.
+    fn argument_type(self) -> &'static str {
+        match self {
+            EventKind::KeyPressed | EventKind::KeyReleased => "string",
+            EventKind::MouseButtonPressed | EventKind::MouseButtonReleased => "number",
+            EventKind::MouseMoved => "Vector2",
+        }
+    }
+
+    fn from_str(name: &str) -> Option<EventKind> {
+        Self::ALL.iter().copied().find(|kind| kind.name() == name)
+    }
+
+    fn of(event: &InputEvent) -> EventKind {
+        match event {
+            InputEvent::KeyPressed(_) => EventKind::KeyPressed,
+            InputEvent::KeyReleased(_) => EventKind::KeyReleased,
+            InputEvent::MouseButtonPressed(_) => EventKind::MouseButtonPressed,
+            InputEvent::MouseButtonReleased(_) => EventKind::MouseButtonReleased,
+            InputEvent::MouseMoved(_) => EventKind::MouseMoved,
+        }
+    }
+}
+
+#[derive(Trace, Finalize)]
+struct Subscription {
+    id: u32,
+    #[unsafe_ignore_trace]
+    kind: EventKind,
+    callback: JsObject,
+}
+
+/// Callbacks registered by scripts, stored in JS realm.
+#[derive(Default, Trace, Finalize, JsData)]
+struct Subscriptions {
+    last_id: u32,
+    subscriptions: Vec<Subscription>,
+}
+
+pub fn register(context: &mut Context) {
+    context.realm().host_defined_mut().insert(Subscriptions::default());
+
+    // argument types by event name, so that callbacks are typed in `Input.on`
+    let events: Vec<String> = EventKind::ALL
+        .iter()
+        .map(|kind| format!("{}: {}", kind.name(), kind.argument_type()))
+        .collect();
+    typings::declare_type(context, "InputEvents", &format!("{{ {} }}", events.join("; ")));
+
+    let key = "(key: string): boolean";
+    let button = "(button: number): boolean";
+    GlobalObject::new("Input")
+        .function("isKeyDown", NativeFunction::from_fn_ptr(is_key_down), key)
+        .function(
+            "keyPressedInLastFrame",
+            NativeFunction::from_fn_ptr(key_pressed_in_last_frame),
+            key,
+        )
+        .function(
+            "isMouseButtonDown",
+            NativeFunction::from_fn_ptr(is_mouse_button_down),
+            button,
+        )
+        // older name used by existing scripts
+        .function(
+            "isMouseKeyPressed",
+            NativeFunction::from_fn_ptr(is_mouse_button_down),
+            button,
+        )
+        .function(
+            "mouseButtonPressedInLastFrame",
+            NativeFunction::from_fn_ptr(mouse_button_pressed_in_last_frame),
+            button,
+        )
+        .function(
+            "mousePosition",
+            NativeFunction::from_fn_ptr(mouse_position),
+            "(): Vector2",
+        )
+        .function("mouseDelta", NativeFunction::from_fn_ptr(mouse_delta), "(): Vector2")
+        .function(
+            "on",
+            NativeFunction::from_fn_ptr(on),
+            "<K extends keyof InputEvents>(kind: K, callback: (value: InputEvents[K]) => void): number",
+        )
+        .function("off", NativeFunction::from_fn_ptr(off), "(id: number): boolean")
+        .register(context);
+}
+
+/// Calls callbacks subscribed to given events. Events should already be filtered by UI.
+pub fn dispatch(context: &mut Context, events: &[InputEvent]) {
+    for event in events {
+        let kind = EventKind::of(event);
+        let callbacks: Vec<JsObject> = {
+            let host_defined = context.realm().host_defined();
+            let subscriptions = host_defined.get::<Subscriptions>().unwrap();
+            subscriptions
+                .subscriptions
+                .iter()
+                .filter(|subscription| subscription.kind == kind)
+                .map(|subscription| subscription.callback.clone())
+                .collect()
+        };
+        if callbacks.is_empty() {
+            continue;
+        }
+        let argument = match event {
+            InputEvent::KeyPressed(key) | InputEvent::KeyReleased(key) => js_string!(key.to_str()).into(),
+            InputEvent::MouseButtonPressed(button) | InputEvent::MouseButtonReleased(button) => {
+                JsValue::from(*button as u32)
+            }
+            InputEvent::MouseMoved(position) => math::to_js(context, *position),
+        };
+        for callback in callbacks {
+            if let Err(error) = callback.call(&JsValue::undefined(), &[argument.clone()], context) {
+                let entry = console::error_entry(&error, Some("Input".to_owned()), None, None);
+                let _ = with_world(|world| console::report(world, entry));
+            }
+        }
+    }
+}
+
+fn key_arg(args: &[JsValue], context: &mut Context) -> JsResult<KeyCode> {
+    let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    match KeyCode::from_string(&name) {
+        KeyCode::None => Err(JsNativeError::range()
+            .with_message(format!("unknown key '{}'", name))
+            .into()),
+        key => Ok(key),
+    }
+}
+
+fn button_arg(args: &[JsValue], context: &mut Context) -> JsResult<usize> {
+    let button = args.get_or_undefined(0).to_number(context)?;
+    if button < 0.0 || button >= MOUSE_BUTTONS as f64 || button.fract() != 0.0 {
+        return Err(JsNativeError::range()
+            .with_message(format!("mouse button must be integer from 0 to {}", MOUSE_BUTTONS - 1))
+            .into());
+    }
+    Ok(button as usize)
+}
+
+fn is_key_down(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let key = key_arg(args, context)?;
+    let down = with_world(|world| world.resources.get::<KeyboardState>().unwrap().is_button_down(key))?;
+    Ok(down.into())
+}
+
+fn key_pressed_in_last_frame(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let key = key_arg(args, context)?;
+    let pressed = with_world(|world| {
+        world
+            .resources
+            .get::<KeyboardState>()
+            .unwrap()
+            .button_pressed_in_last_frame(key)
+    })?;
+    Ok(pressed.into())
+}
+
+fn is_mouse_button_down(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let button = button_arg(args, context)?;
+    let down = with_world(|world| world.resources.get::<MouseState>().unwrap().is_button_down(button))?;
+    Ok(down.into())
+}
+
+fn mouse_button_pressed_in_last_frame(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let button = button_arg(args, context)?;
+    let pressed = with_world(|world| {
+        world
+            .resources
+            .get::<MouseState>()
+            .unwrap()
+            .button_pressed_in_last_frame(button)
+    })?;
+    Ok(pressed.into())
+}
+
+/// Cursor position in window pixels as `Math.Vector2`.
+fn mouse_position(_this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let position = with_world(|world| world.resources.get::<MouseState>().unwrap().get_mouse_position())?;
+    Ok(math::to_js(context, Vec2::from(position)))
+}
+
+/// Cursor movement since last frame as `Math.Vector2`.
+fn mouse_delta(_this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let delta = with_world(|world| world.resources.get::<MouseState>().unwrap().get_mouse_move())?;
+    Ok(math::to_js(context, Vec2::from(delta)))
+}
+
+/// `Input.on(kind, callback)`, returns id to be passed to `Input.off`.
+fn on(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let kind = EventKind::from_str(&name)
+        .ok_or_else(|| JsNativeError::range().with_message(format!("unknown input event '{}'", name)))?;
+    let callback = args
+        .get_or_undefined(1)
+        .as_callable()
+        .cloned()
+        .ok_or_else(|| JsNativeError::typ().with_message("callback is not a function"))?;
+
+    let realm = context.realm().clone();
+    let mut host_defined = realm.host_defined_mut();
+    let subscriptions = host_defined.get_mut::<Subscriptions>().unwrap();
+    subscriptions.last_id += 1;
+    let id = subscriptions.last_id;
+    subscriptions.subscriptions.push(Subscription { id, kind, callback });
+    Ok(id.into())
+}
+
+fn off(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let id = args.get_or_undefined(0).to_number(context)?;
+    let realm = context.realm().clone();
+    let mut host_defined = realm.host_defined_mut();
+    let subscriptions = host_defined.get_mut::<Subscriptions>().unwrap();
+    let count = subscriptions.subscriptions.len();
+    subscriptions
+        .subscriptions
+        .retain(|subscription| subscription.id as f64 != id);
+    Ok((subscriptions.subscriptions.len() != count).into())
+}
@@ -0,0 +1,419 @@
+use boa_engine::context::HostHooks;
+use boa_engine::object::FunctionObjectBuilder;
+use boa_engine::property::Attribute;
+use boa_engine::realm::Realm;
+use boa_engine::NativeFunction;
+use boa_engine::{js_string, Context, JsError, JsNativeError, JsNativeErrorKind, JsResult, JsString, JsValue};
+use boa_interner::Interner;
+use boa_parser::{Parser, Source};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicIsize, AtomicU32, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use super::tokens::{tokenize, TokenKind};
+
+/// Limits enforced on scripts. Controller breaking any of them is disabled until its script is reloaded.
+///
+/// Scripts have no filesystem, network or process access, assets are only reachable through `Resources`.
+/// They can't compile code from strings either (`eval`, `new Function`), so all their code counts steps.
+/// Can be changed at runtime through world resources, new values are used from next frame.
+#[derive(Debug, Clone, Copy)]
+pub struct ScriptLimits {
+    /// Time single hook call may take. `update(dt)` is called once per frame,
+    /// so it's also controller's per-frame budget.
+    pub hook_time: Duration,
+    /// Loop iterations and function calls single hook call may make, including ones of functions it calls.
+    pub hook_steps: u64,
+    /// Iterations of a single loop, stops infinite loops before they freeze the game.
+    pub loop_iterations: u64,
+    pub recursion_depth: usize,
+    /// Bytes allocated by each controller and not yet freed. Only enforced when binary uses
+    /// `ScriptAllocator` as its global allocator.
+    pub memory: usize,
+}
+
+impl Default for ScriptLimits {
+    fn default() -> Self {
+        ScriptLimits {
+            hook_time: Duration::from_millis(20),
+            hook_steps: 5_000_000,
+            loop_iterations: 1_000_000,
+            recursion_depth: 512,
+            memory: 256 * 1024 * 1024,
+        }
+    }
+}
+
+impl ScriptLimits {
+    pub fn apply(&self, context: &mut Context) {
+        let runtime_limits = context.runtime_limits_mut();
+        runtime_limits.set_loop_iteration_limit(self.loop_iterations);
+        runtime_limits.set_recursion_limit(self.recursion_depth);
+        MEMORY_LIMIT.store(self.memory, Ordering::Relaxed);
+    }
+
+    /// Reason to disable controller `owner` after it used `elapsed` time in hook that ended with `result`.
+    /// Hooks are stopped as soon as they break limits (see `run_hook`), this reports why.
+    pub fn check<T>(&self, result: &Result<T, JsError>, elapsed: Duration, owner: Option<u32>) -> Option<String> {
+        if let Err(error) = result {
+            let runtime_limit = error
+                .as_native()
+                .map(|error| matches!(error.kind, JsNativeErrorKind::RuntimeLimit))
+                .unwrap_or(false);
+            if runtime_limit {
+                return Some(error.to_string());
+            }
+        }
+        // native calls can't be interrupted
+        if elapsed > self.hook_time {
+            return Some(format!("took {:?}, limit is {:?}", elapsed, self.hook_time));
+        }
+        if owner_memory(owner.unwrap_or(0)) > self.memory {
+            // garbage also counts until it's collected
+            boa_gc::force_collect();
+            let used = owner_memory(owner.unwrap_or(0));
+            if used > self.memory {
+                return Some(format!("uses {} bytes, limit is {}", used, self.memory));
+            }
+        }
+        None
+    }
+}
+
+/// Registers function counting steps of hooks, calls to it are added to scripts by `instrument`.
+pub fn register(context: &mut Context) {
+    // not writable, so scripts can't replace it
+    let tick = NativeFunction::from_fn_ptr(tick);
+    let tick = FunctionObjectBuilder::new(context.realm(), tick)
+        .name(js_string!(TICK))
+        .build();
+    context
+        .register_global_property(js_string!(TICK), tick, Attribute::empty())
+        .unwrap();
+}
+
+/// Host hooks of script context, compiling code from strings would skip `instrument`.
+pub struct ScriptHooks;
+
+impl HostHooks for ScriptHooks {
+    fn ensure_can_compile_strings(
+        &self,
+        _realm: Realm,
+        _parameters: &[JsString],
+        _body: &JsString,
+        _direct: bool,
+        _context: &mut Context,
+    ) -> JsResult<()> {
+        Err(JsNativeError::eval()
+            .with_message("compiling code from strings is not allowed")
+            .into())
+    }
+
+    fn max_buffer_size(&self, _context: &mut Context) -> u64 {
+        MEMORY_LIMIT.load(Ordering::Relaxed) as u64
+    }
+}
+
+const TICK: &str = "__tick";
+
+/// Adds a step to loop bodies and function bodies of script, so hooks can be stopped while they run.
+/// Calls are inserted on the lines they belong to, so positions in errors keep their lines.
+/// Script that doesn't compile is returned as it is, for its errors to point at the right place.
+pub fn instrument(source: &str) -> String {
+    let compiles = |source: &str| {
+        Parser::new(Source::from_bytes(source))
+            .parse_script(&mut Interner::default())
+            .is_ok()
+    };
+    if !compiles(source) {
+        return source.to_owned();
+    }
+    let tokens = tokenize(source);
+    // keyword or name before each opened bracket, `None` for others than `(`
+    let mut open: Vec<Option<&str>> = vec![];
+    let mut closed_call = None;
+    let mut class_body = None;
+    let mut steps = vec![];
+    for (i, token) in tokens.iter().enumerate() {
+        let previous = if i > 0 { Some(tokens[i - 1]) } else { None };
+        match (token.kind, token.text) {
+            (TokenKind::Word, "class") => class_body = Some(open.len()),
+            (TokenKind::Open, "(") => open.push(previous.filter(|p| p.kind == TokenKind::Word).map(|p| p.text)),
+            (TokenKind::Open, "{") => {
+                let body = match previous {
+                    _ if class_body == Some(open.len()) => {
+                        class_body = None;
+                        false
+                    }
+                    Some(previous) if previous.text == ")" => {
+                        !matches!(closed_call, Some("if") | Some("switch") | Some("catch") | Some("with"))
+                    }
+                    Some(previous) => previous.text == "do" || previous.text == "=>",
+                    None => false,
+                };
+                if body {
+                    // steps go after directives, like "use strict", body starting with one without `;` is skipped
+                    let directive = tokens
+                        .get(i + 1)
+                        .filter(|t| t.kind == TokenKind::Literal && t.text.starts_with(['"', '\'']));
+                    match (directive, tokens.get(i + 2)) {
+                        (Some(_), Some(end)) if end.text == ";" => steps.push(end.start + 1),
+                        (Some(_), _) => (),
+                        (None, _) => steps.push(token.start + 1),
+                    }
+                }
+                open.push(None);
+            }
+            (TokenKind::Open, _) => open.push(None),
+            (TokenKind::Close, text) => {
+                let opened = open.pop().flatten();
+                closed_call = if text == ")" { opened } else { None };
+            }
+            _ => (),
+        }
+    }
+
+    let mut instrumented = String::with_capacity(source.len() + steps.len() * (TICK.len() + 3));
+    let mut copied = 0;
+    for step in steps {
+        instrumented.push_str(&source[copied..step]);
+        instrumented.push_str(TICK);
+        instrumented.push_str("();");
+        copied = step;
+    }
+    instrumented.push_str(&source[copied..]);
+    if compiles(&instrumented) {
+        instrumented
+    } else {
+        println!("couldn't instrument script, its hooks can't be stopped while they run");
+        source.to_owned()
+    }
+}
+
+/// Hook call in progress, checked by every step.
+#[derive(Clone, Copy)]
+struct Budget {
+    steps: u64,
+    started: Instant,
+    hook_time: Duration,
+}
+
+/// Steps between checks of hook time.
+const STEPS_PER_CLOCK_CHECK: u64 = 256;
+
+thread_local! {
+    static BUDGET: Cell<Option<Budget>> = const { Cell::new(None) };
+    static COUNTING: Cell<bool> = const { Cell::new(false) };
+    /// Controller whose code runs, 0 for scripts outside of controllers.
+    static OWNER: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Runs hook of controller `owner` (or script code outside of controllers) within `limits`.
+/// It is stopped by uncatchable error once it breaks them.
+pub fn run_hook<R, F: FnOnce() -> R>(limits: &ScriptLimits, owner: Option<u32>, f: F) -> R {
+    let budget = Budget {
+        steps: limits.hook_steps,
+        started: Instant::now(),
+        hook_time: limits.hook_time,
+    };
+    let previous_budget = BUDGET.with(|b| b.replace(Some(budget)));
+    let previous_owner = OWNER.with(|o| o.replace(owner.unwrap_or(0)));
+    let result = f();
+    OWNER.with(|o| o.set(previous_owner));
+    BUDGET.with(|b| b.set(previous_budget));
+    result
+}
+
+fn tick(_this: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+    let mut budget = match BUDGET.with(|b| b.get()) {
+        Some(budget) => budget,
+        None => return Ok(JsValue::undefined()),
+    };
+    if budget.steps == 0 {
+        return Err(JsNativeError::runtime_limit()
+            .with_message("hook made too many steps (loop iterations and calls)")
+            .into());
+    }
+    budget.steps -= 1;
+    if budget.steps % STEPS_PER_CLOCK_CHECK == 0 && budget.started.elapsed() > budget.hook_time {
+        return Err(JsNativeError::runtime_limit()
+            .with_message(format!("hook took more than {:?}", budget.hook_time))
+            .into());
+    }
+    BUDGET.with(|b| b.set(Some(budget)));
+    let owner = OWNER.with(|o| o.get());
+    let used = owner_memory(owner);
+    let limit = MEMORY_LIMIT.load(Ordering::Relaxed);
+    if used > limit {
+        return Err(JsNativeError::runtime_limit()
+            .with_message(format!("uses {} bytes, limit is {}", used, limit))
+            .into());
+    }
+    Ok(JsValue::undefined())
+}
+
+/// Counts memory allocated by scripts (while `set_counting(true, ..)` runs on the current thread)
+/// for each controller, so `ScriptLimits::memory` can be enforced while hooks run.
+/// Allocations remember their controller, memory freed later by garbage collector is subtracted from it.
+/// Every allocation of the program gets a small header for that, so it's up to binary to use it:
+/// `#[global_allocator] static ALLOCATOR: ScriptAllocator = ScriptAllocator;`
+pub struct ScriptAllocator;
+
+/// Controllers whose memory is counted at once, further ones are counted only in total.
+const OWNER_SLOTS: usize = 1024;
+/// Slots tried for a controller, starting from its id.
+const OWNER_PROBES: usize = 16;
+const FREE_SLOT: u32 = u32::MAX;
+/// Header value of allocations not made by scripts.
+const NOT_COUNTED: u32 = u32::MAX;
+
+struct OwnerMemory {
+    owner: AtomicU32,
+    bytes: AtomicIsize,
+}
+
+// only used to initialize the statics below
+#[allow(clippy::declare_interior_mutable_const)]
+const EMPTY_SLOT: OwnerMemory = OwnerMemory {
+    owner: AtomicU32::new(FREE_SLOT),
+    bytes: AtomicIsize::new(0),
+};
+
+static OWNERS: [OwnerMemory; OWNER_SLOTS] = [EMPTY_SLOT; OWNER_SLOTS];
+/// Scripts outside of controllers.
+static UNOWNED: OwnerMemory = OwnerMemory {
+    owner: AtomicU32::new(0),
+    bytes: AtomicIsize::new(0),
+};
+static SCRIPT_MEMORY: AtomicIsize = AtomicIsize::new(0);
+static MEMORY_LIMIT: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+fn slot(owner: u32) -> Option<&'static OwnerMemory> {
+    if owner == 0 {
+        return Some(&UNOWNED);
+    }
+    (0..OWNER_PROBES)
+        .map(|probe| &OWNERS[(owner as usize + probe) % OWNER_SLOTS])
+        .find(|slot| slot.owner.load(Ordering::Relaxed) == owner)
+}
+
+/// Starts counting memory of controller `owner` separately.
+pub fn track_owner(owner: u32) {
+    for probe in 0..OWNER_PROBES {
+        let slot = &OWNERS[(owner as usize + probe) % OWNER_SLOTS];
+        if slot
+            .owner
+            .compare_exchange(FREE_SLOT, owner, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+        {
+            slot.bytes.store(0, Ordering::Relaxed);
+            return;
+        }
+    }
+}
+
+/// Stops counting memory of removed controller, its remaining allocations are counted only in total.
+pub fn forget_owner(owner: u32) {
+    if let Some(slot) = slot(owner).filter(|_| owner != 0) {
+        slot.owner.store(FREE_SLOT, Ordering::Relaxed);
+    }
+}
+
+fn current_owner() -> u32 {
+    if COUNTING.try_with(|counting| counting.get()).unwrap_or(false) {
+        OWNER.try_with(|owner| owner.get()).unwrap_or(0)
+    } else {
+        NOT_COUNTED
+    }
+}
+
+fn track(owner: u32, bytes: isize) {
+    if owner == NOT_COUNTED {
+        return;
+    }
+    SCRIPT_MEMORY.fetch_add(bytes, Ordering::Relaxed);
+    if let Some(slot) = slot(owner) {
+        slot.bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+}
+
+/// Header goes right before returned pointer, its size keeps the pointer aligned.
+fn with_header(layout: Layout) -> Option<(Layout, usize)> {
+    let header = layout.align().max(std::mem::size_of::<u64>());
+    let size = layout.size().checked_add(header)?;
+    Some((Layout::from_size_align(size, layout.align()).ok()?, header))
+}
+
+unsafe fn owner_of(ptr: *mut u8) -> u32 {
+    (ptr.sub(std::mem::size_of::<u32>()) as *const u32).read_unaligned()
+}
+
+unsafe fn finish_alloc(base: *mut u8, header: usize, size: usize) -> *mut u8 {
+    if base.is_null() {
+        return base;
+    }
+    let ptr = base.add(header);
+    let owner = current_owner();
+    (ptr.sub(std::mem::size_of::<u32>()) as *mut u32).write_unaligned(owner);
+    track(owner, size as isize);
+    ptr
+}
+
+unsafe impl GlobalAlloc for ScriptAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        match with_header(layout) {
+            Some((full, header)) => finish_alloc(System.alloc(full), header, layout.size()),
+            None => std::ptr::null_mut(),
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let (full, header) = with_header(layout).unwrap();
+        track(owner_of(ptr), -(layout.size() as isize));
+        System.dealloc(ptr.sub(header), full);
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        match with_header(layout) {
+            Some((full, header)) => finish_alloc(System.alloc_zeroed(full), header, layout.size()),
+            None => std::ptr::null_mut(),
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let (full, header) = with_header(layout).unwrap();
+        let new_full = match new_size.checked_add(header) {
+            Some(new_full) => new_full,
+            None => return std::ptr::null_mut(),
+        };
+        // header is copied together with data, allocation stays with its owner
+        let owner = owner_of(ptr);
+        let base = System.realloc(ptr.sub(header), full, new_full);
+        if base.is_null() {
+            return base;
+        }
+        track(owner, new_size as isize - layout.size() as isize);
+        base.add(header)
+    }
+}
+
+/// Runs `f` with its allocations counted as script memory, or not counted when `counting` is false.
+pub fn set_counting<R, F: FnOnce() -> R>(counting: bool, f: F) -> R {
+    let previous = COUNTING.with(|c| c.replace(counting));
+    let result = f();
+    COUNTING.with(|c| c.set(previous));
+    result
+}
+
+/// Bytes allocated by scripts and not yet freed.
+pub fn script_memory() -> usize {
+    SCRIPT_MEMORY.load(Ordering::Relaxed).max(0) as usize
+}
+
+/// Bytes allocated by controller `owner` (0 for scripts outside of controllers) and not yet freed.
+pub fn owner_memory(owner: u32) -> usize {
+    slot(owner)
+        .map(|slot| slot.bytes.load(Ordering::Relaxed).max(0) as usize)
+        .unwrap_or(0)
+}
@@ -0,0 +1,717 @@
+use boa_engine::class::{Class, ClassBuilder};
+use boa_engine::object::builtins::JsArray;
+use boa_engine::object::JsData;
+use boa_engine::{js_string, Context, JsArgs, JsNativeError, JsObject, JsResult, JsValue, NativeFunction};
+use boa_gc::{Finalize, Trace};
+use just_core::glam::EulerRot;
+use just_core::math::{Matrix, Quat, Vec2, Vec3, Vec4};
+
+use super::typings::{self, DeclaredClass, Interface, Placement};
+
+type NativeFn = fn(&JsValue, &[JsValue], &mut Context) -> JsResult<JsValue>;
+
+/// Math type exposed to scripts under `Math` namespace.
+/// Every JS object holds its own copy of the value, values are copied when they cross the boundary,
+/// so scripts can never alias engine memory.
+pub trait MathValue: Copy + PartialEq + 'static {
+    const NAME: &'static str;
+    /// Names of components readable and writable as properties.
+    const COMPONENTS: &'static [&'static str];
+
+    fn component(&self, index: usize) -> f32;
+    fn set_component(&mut self, index: usize, value: f32);
+    /// Flat list of numbers held by value, matrices are column major.
+    fn elements(&self) -> Vec<f32> {
+        (0..Self::COMPONENTS.len()).map(|index| self.component(index)).collect()
+    }
+    /// TypeScript signature of constructor, by default all components are optional.
+    fn constructor_signature() -> String {
+        let components: Vec<String> = Self::COMPONENTS.iter().map(|c| format!("{}?: number", c)).collect();
+        format!("({})", components.join(", "))
+    }
+    /// Builds value from constructor arguments.
+    fn construct(args: &[JsValue], context: &mut Context) -> JsResult<Self>;
+    /// Adds type specific methods to the class.
+    fn init(class: &mut DeclaredClass<'_, '_>);
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MathObject<T: MathValue> {
+    pub value: T,
+}
+
+impl<T: MathValue> Finalize for MathObject<T> {}
+
+// SAFETY: math values are plain numbers, they don't hold any GC pointers
+unsafe impl<T: MathValue> Trace for MathObject<T> {
+    boa_gc::empty_trace!();
+}
+
+impl<T: MathValue> JsData for MathObject<T> {}
+
+impl<T: MathValue> Class for MathObject<T> {
+    const NAME: &'static str = T::NAME;
+
+    fn init(class: &mut ClassBuilder<'_>) -> JsResult<()> {
+        let constructor = T::constructor_signature();
+        let mut class = DeclaredClass::new(class, T::NAME, Some(&constructor));
+        class.enumerable();
+        for (index, name) in T::COMPONENTS.iter().enumerate() {
+            let get = NativeFunction::from_copy_closure(move |this, _args, _context| {
+                Ok(this_value::<T>(this)?.component(index).into())
+            });
+            let set = NativeFunction::from_copy_closure(move |this, args, context| {
+                let value = args.get_or_undefined(0).to_number(context)? as f32;
+                let object = this_object::<T>(this)?;
+                object
+                    .downcast_mut::<MathObject<T>>()
+                    .unwrap()
+                    .value
+                    .set_component(index, value);
+                Ok(JsValue::undefined())
+            });
+            class.accessor(name, get, Some(set), "number");
+        }
+        class
+            .method(
+                "clone",
+                NativeFunction::from_fn_ptr(clone::<T>),
+                &format!("(): {}", T::NAME),
+            )
+            .method(
+                "equals",
+                NativeFunction::from_fn_ptr(equals::<T>),
+                &format!("(other: {}): boolean", T::NAME),
+            )
+            .method("toArray", NativeFunction::from_fn_ptr(to_array::<T>), "(): number[]")
+            .method("toString", NativeFunction::from_fn_ptr(to_string::<T>), "(): string");
+        T::init(&mut class);
+        class.finish();
+        Ok(())
+    }
+
+    fn data_constructor(_new_target: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<Self> {
+        Ok(MathObject {
+            value: T::construct(args, context)?,
+        })
+    }
+}
+
+/// Creates new JS object holding copy of `value`.
+pub fn to_js<T: MathValue>(context: &mut Context, value: T) -> JsValue {
+    MathObject::from_data(MathObject { value }, context).unwrap().into()
+}
+
+/// Copies value out of JS object of matching class.
+/// For types with components any object having them is accepted too, e.g. `{x: 1, y: 2}`.
+pub fn from_js<T: MathValue>(value: &JsValue, context: &mut Context) -> JsResult<T> {
+    let object = value
+        .as_object()
+        .ok_or_else(|| JsNativeError::typ().with_message(format!("expected Math.{}", T::NAME)))?;
+    if let Some(math) = object.downcast_ref::<MathObject<T>>() {
+        return Ok(math.value);
+    }
+    if T::COMPONENTS.is_empty() {
+        return Err(JsNativeError::typ()
+            .with_message(format!("expected Math.{}", T::NAME))
+            .into());
+    }
+    let mut components = Vec::with_capacity(T::COMPONENTS.len());
+    for name in T::COMPONENTS {
+        let component = object.get(js_string!(*name), context)?;
+        components.push(component.to_number(context)?.into());
+    }
+    T::construct(&components, context)
+}
+
+/// Adds math classes and helpers to the builtin `Math` object.
+pub fn register(context: &mut Context) -> JsResult<()> {
+    let math = context.global_object().get(js_string!("Math"), context)?;
+    let math = math.as_object().unwrap().clone();
+    register_class::<Vec2>(context, &math)?;
+    register_class::<Vec3>(context, &math)?;
+    register_class::<Vec4>(context, &math)?;
+    register_class::<Quat>(context, &math)?;
+    register_class::<Matrix>(context, &math)?;
+
+    let realm = context.realm().clone();
+    let functions: [(&str, NativeFn, &str); 3] = [
+        ("Sin", sin, "(angle: number): number"),
+        ("Cos", cos, "(angle: number): number"),
+        (
+            "quaternionFromEulerAngles",
+            quat_from_euler,
+            "(x: number | Vector3, y?: number, z?: number): Quat",
+        ),
+    ];
+    // builtin `Math` is already declared by TypeScript, declaration only adds to it
+    let mut declaration = Interface::new("Math", Placement::None);
+    for (name, function, signature) in functions.iter() {
+        let function = NativeFunction::from_fn_ptr(*function).to_js_function(&realm);
+        math.set(js_string!(*name), function, true, context)?;
+        declaration = declaration.method(name, signature);
+    }
+    typings::declare(context, declaration);
+    Ok(())
+}
+
+/// Classes are registered globally by boa, they are moved to `Math` afterwards.
+fn register_class<T: MathValue>(context: &mut Context, math: &JsObject) -> JsResult<()> {
+    context.register_global_class::<MathObject<T>>()?;
+    let global = context.global_object();
+    let constructor = global.get(js_string!(T::NAME), context)?;
+    global.delete_property_or_throw(js_string!(T::NAME), context)?;
+    math.set(js_string!(T::NAME), constructor, true, context)?;
+    typings::place(context, T::NAME, Placement::Property("Math".to_owned()));
+    Ok(())
+}
+
+fn this_object<T: MathValue>(this: &JsValue) -> JsResult<JsObject> {
+    match this.as_object() {
+        Some(object) if object.is::<MathObject<T>>() => Ok(object.clone()),
+        _ => Err(JsNativeError::typ()
+            .with_message(format!("'this' is not a Math.{}", T::NAME))
+            .into()),
+    }
+}
+
+fn this_value<T: MathValue>(this: &JsValue) -> JsResult<T> {
+    let object = this_object::<T>(this)?;
+    let value = object.downcast_ref::<MathObject<T>>().unwrap().value;
+    Ok(value)
+}
+
+fn number_arg(args: &[JsValue], index: usize, context: &mut Context) -> JsResult<f32> {
+    Ok(args.get_or_undefined(index).to_number(context)? as f32)
+}
+
+/// Components given as separate numbers, missing ones are `default`.
+fn component_args<const N: usize>(args: &[JsValue], default: f32, context: &mut Context) -> JsResult<[f32; N]> {
+    let mut components = [default; N];
+    for (index, component) in components.iter_mut().enumerate() {
+        if let Some(arg) = args.get(index) {
+            *component = arg.to_number(context)? as f32;
+        }
+    }
+    Ok(components)
+}
+
+fn clone<T: MathValue>(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<T>(this)?;
+    Ok(to_js(context, value))
+}
+
+fn equals<T: MathValue>(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<T>(this)?;
+    let other = from_js::<T>(args.get_or_undefined(0), context)?;
+    Ok((value == other).into())
+}
+
+fn to_array<T: MathValue>(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let elements = this_value::<T>(this)?.elements();
+    let array = JsArray::from_iter(elements.into_iter().map(JsValue::from), context);
+    Ok(array.into())
+}
+
+fn to_string<T: MathValue>(this: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<T>(this)?;
+    let components: Vec<String> = value.elements().iter().map(|c| c.to_string()).collect();
+    Ok(js_string!(format!("{}({})", T::NAME, components.join(", "))).into())
+}
+
+fn sin(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    Ok(args.get_or_undefined(0).to_number(context)?.sin().into())
+}
+
+fn cos(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    Ok(args.get_or_undefined(0).to_number(context)?.cos().into())
+}
+
+/// Euler angles are applied in XYZ order, same as in scene files.
+fn quat_from_euler(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let angles = match args.len() {
+        1 => from_js::<Vec3>(&args[0], context)?,
+        _ => Vec3::from(component_args::<3>(args, 0.0, context)?),
+    };
+    Ok(to_js(
+        context,
+        Quat::from_euler(EulerRot::XYZ, angles.x, angles.y, angles.z),
+    ))
+}
+
+/// Operations shared by all vector types.
+macro_rules! vector_value {
+    ($type:ty, $name:literal, [$($component:literal),+], $size:literal) => {
+        impl MathValue for $type {
+            const NAME: &'static str = $name;
+            const COMPONENTS: &'static [&'static str] = &[$($component),+];
+
+            fn component(&self, index: usize) -> f32 {
+                self[index]
+            }
+
+            fn set_component(&mut self, index: usize, value: f32) {
+                self[index] = value;
+            }
+
+            fn construct(args: &[JsValue], context: &mut Context) -> JsResult<Self> {
+                Ok(<$type>::from(component_args::<$size>(args, 0.0, context)?))
+            }
+
+            fn init(class: &mut DeclaredClass<'_, '_>) {
+                vector_methods::<$type>(class);
+            }
+        }
+
+        impl VectorValue for $type {
+            fn add(self, other: Self) -> Self {
+                self + other
+            }
+            fn sub(self, other: Self) -> Self {
+                self - other
+            }
+            fn mul(self, other: Self) -> Self {
+                self * other
+            }
+            fn div(self, other: Self) -> Self {
+                self / other
+            }
+            fn scale(self, factor: f32) -> Self {
+                self * factor
+            }
+            fn dot(self, other: Self) -> f32 {
+                <$type>::dot(self, other)
+            }
+            fn length(self) -> f32 {
+                <$type>::length(self)
+            }
+            fn length_squared(self) -> f32 {
+                <$type>::length_squared(self)
+            }
+            fn distance(self, other: Self) -> f32 {
+                <$type>::distance(self, other)
+            }
+            fn normalize(self) -> Self {
+                <$type>::normalize_or_zero(self)
+            }
+            fn lerp(self, other: Self, t: f32) -> Self {
+                <$type>::lerp(self, other, t)
+            }
+        }
+    };
+}
+
+trait VectorValue: MathValue {
+    fn add(self, other: Self) -> Self;
+    fn sub(self, other: Self) -> Self;
+    fn mul(self, other: Self) -> Self;
+    fn div(self, other: Self) -> Self;
+    fn scale(self, factor: f32) -> Self;
+    fn dot(self, other: Self) -> f32;
+    fn length(self) -> f32;
+    fn length_squared(self) -> f32;
+    fn distance(self, other: Self) -> f32;
+    /// Zero vector stays zero instead of becoming NaN.
+    fn normalize(self) -> Self;
+    fn lerp(self, other: Self, t: f32) -> Self;
+}
+
+vector_value!(Vec2, "Vector2", ["x", "y"], 2);
+vector_value!(Vec3, "Vector3", ["x", "y", "z"], 3);
+vector_value!(Vec4, "Vector4", ["x", "y", "z", "w"], 4);
+
+fn vector_methods<T: VectorValue>(class: &mut DeclaredClass<'_, '_>) {
+    // `{}` is replaced with name of the type
+    let methods: [(&str, NativeFn, &str); 11] = [
+        ("add", vector_add::<T>, "(other: {}): {}"),
+        ("sub", vector_sub::<T>, "(other: {}): {}"),
+        ("mul", vector_mul::<T>, "(other: {} | number): {}"),
+        ("div", vector_div::<T>, "(other: {} | number): {}"),
+        ("negate", vector_negate::<T>, "(): {}"),
+        ("dot", vector_dot::<T>, "(other: {}): number"),
+        ("length", vector_length::<T>, "(): number"),
+        ("lengthSquared", vector_length_squared::<T>, "(): number"),
+        ("distance", vector_distance::<T>, "(other: {}): number"),
+        ("normalize", vector_normalize::<T>, "(): {}"),
+        ("lerp", vector_lerp::<T>, "(other: {}, t: number): {}"),
+    ];
+    for (name, function, signature) in methods.iter() {
+        let signature = signature.replace("{}", T::NAME);
+        class.method(name, NativeFunction::from_fn_ptr(*function), &signature);
+    }
+    if T::NAME == Vec3::NAME {
+        class.method(
+            "cross",
+            NativeFunction::from_fn_ptr(vector_cross),
+            "(other: Vector3): Vector3",
+        );
+    }
+}
+
+fn vector_add<T: VectorValue>(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<T>(this)?;
+    let other = from_js::<T>(args.get_or_undefined(0), context)?;
+    Ok(to_js(context, value.add(other)))
+}
+
+fn vector_sub<T: VectorValue>(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<T>(this)?;
+    let other = from_js::<T>(args.get_or_undefined(0), context)?;
+    Ok(to_js(context, value.sub(other)))
+}
+
+/// Multiplies by number or component-wise by other vector.
+fn vector_mul<T: VectorValue>(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<T>(this)?;
+    let arg = args.get_or_undefined(0);
+    let result = match arg.as_number() {
+        Some(factor) => value.scale(factor as f32),
+        None => value.mul(from_js::<T>(arg, context)?),
+    };
+    Ok(to_js(context, result))
+}
+
+/// Divides by number or component-wise by other vector.
+fn vector_div<T: VectorValue>(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<T>(this)?;
+    let arg = args.get_or_undefined(0);
+    let result = match arg.as_number() {
+        Some(divisor) => value.scale(1.0 / divisor as f32),
+        None => value.div(from_js::<T>(arg, context)?),
+    };
+    Ok(to_js(context, result))
+}
+
+fn vector_negate<T: VectorValue>(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<T>(this)?;
+    Ok(to_js(context, value.scale(-1.0)))
+}
+
+fn vector_dot<T: VectorValue>(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<T>(this)?;
+    let other = from_js::<T>(args.get_or_undefined(0), context)?;
+    Ok(value.dot(other).into())
+}
+
+fn vector_length<T: VectorValue>(this: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+    Ok(this_value::<T>(this)?.length().into())
+}
+
+fn vector_length_squared<T: VectorValue>(
+    this: &JsValue,
+    _args: &[JsValue],
+    _context: &mut Context,
+) -> JsResult<JsValue> {
+    Ok(this_value::<T>(this)?.length_squared().into())
+}
+
+fn vector_distance<T: VectorValue>(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<T>(this)?;
+    let other = from_js::<T>(args.get_or_undefined(0), context)?;
+    Ok(value.distance(other).into())
+}
+
+fn vector_normalize<T: VectorValue>(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<T>(this)?;
+    Ok(to_js(context, value.normalize()))
+}
+
+fn vector_lerp<T: VectorValue>(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<T>(this)?;
+    let other = from_js::<T>(args.get_or_undefined(0), context)?;
+    let t = number_arg(args, 1, context)?;
+    Ok(to_js(context, value.lerp(other, t)))
+}
+
+fn vector_cross(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<Vec3>(this)?;
+    let other = from_js::<Vec3>(args.get_or_undefined(0), context)?;
+    Ok(to_js(context, value.cross(other)))
+}
+
+impl MathValue for Quat {
+    const NAME: &'static str = "Quat";
+    const COMPONENTS: &'static [&'static str] = &["x", "y", "z", "w"];
+
+    fn component(&self, index: usize) -> f32 {
+        self.to_array()[index]
+    }
+
+    fn set_component(&mut self, index: usize, value: f32) {
+        let mut components = self.to_array();
+        components[index] = value;
+        *self = Quat::from_array(components);
+    }
+
+    /// `new Math.Quat()` is identity, otherwise takes x, y, z, w.
+    fn construct(args: &[JsValue], context: &mut Context) -> JsResult<Self> {
+        if args.is_empty() {
+            return Ok(Quat::IDENTITY);
+        }
+        Ok(Quat::from_array(component_args::<4>(args, 0.0, context)?))
+    }
+
+    fn init(class: &mut DeclaredClass<'_, '_>) {
+        let methods: [(&str, NativeFn, &str); 8] = [
+            ("mul", quat_mul, "<T extends Quat | Vector3>(other: T): T"),
+            ("inverse", quat_inverse, "(): Quat"),
+            ("normalize", quat_normalize, "(): Quat"),
+            ("dot", quat_dot, "(other: Quat): number"),
+            ("length", quat_length, "(): number"),
+            ("lerp", quat_lerp, "(other: Quat, t: number): Quat"),
+            ("slerp", quat_slerp, "(other: Quat, t: number): Quat"),
+            ("toEuler", quat_to_euler, "(): Vector3"),
+        ];
+        for (name, function, signature) in methods.iter() {
+            class.method(name, NativeFunction::from_fn_ptr(*function), signature);
+        }
+        class
+            .static_method("identity", NativeFunction::from_fn_ptr(quat_identity), "(): Quat")
+            .static_method(
+                "fromEuler",
+                NativeFunction::from_fn_ptr(quat_from_euler),
+                "(x: number | Vector3, y?: number, z?: number): Quat",
+            )
+            .static_method(
+                "fromAxisAngle",
+                NativeFunction::from_fn_ptr(quat_from_axis_angle),
+                "(axis: Vector3, angle: number): Quat",
+            );
+    }
+}
+
+/// Combines rotations for `Math.Quat` argument, rotates vector for `Math.Vector3` argument.
+fn quat_mul(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<Quat>(this)?;
+    let arg = args.get_or_undefined(0);
+    if let Some(vector) = arg
+        .as_object()
+        .and_then(|o| o.downcast_ref::<MathObject<Vec3>>().map(|v| v.value))
+    {
+        return Ok(to_js(context, value * vector));
+    }
+    let other = from_js::<Quat>(arg, context)?;
+    Ok(to_js(context, value * other))
+}
+
+fn quat_inverse(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<Quat>(this)?;
+    Ok(to_js(context, value.inverse()))
+}
+
+fn quat_normalize(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<Quat>(this)?;
+    Ok(to_js(context, value.normalize()))
+}
+
+fn quat_dot(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<Quat>(this)?;
+    let other = from_js::<Quat>(args.get_or_undefined(0), context)?;
+    Ok(value.dot(other).into())
+}
+
+fn quat_length(this: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+    Ok(this_value::<Quat>(this)?.length().into())
+}
+
+fn quat_lerp(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<Quat>(this)?;
+    let other = from_js::<Quat>(args.get_or_undefined(0), context)?;
+    let t = number_arg(args, 1, context)?;
+    Ok(to_js(context, value.lerp(other, t)))
+}
+
+fn quat_slerp(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<Quat>(this)?;
+    let other = from_js::<Quat>(args.get_or_undefined(0), context)?;
+    let t = number_arg(args, 1, context)?;
+    Ok(to_js(context, value.slerp(other, t)))
+}
+
+/// Angles in XYZ order as `Math.Vector3`.
+fn quat_to_euler(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<Quat>(this)?;
+    let (x, y, z) = value.to_euler(EulerRot::XYZ);
+    Ok(to_js(context, Vec3::new(x, y, z)))
+}
+
+fn quat_identity(_this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    Ok(to_js(context, Quat::IDENTITY))
+}
+
+fn quat_from_axis_angle(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let axis = from_js::<Vec3>(args.get_or_undefined(0), context)?;
+    let angle = number_arg(args, 1, context)?;
+    Ok(to_js(context, Quat::from_axis_angle(axis.normalize_or_zero(), angle)))
+}
+
+impl MathValue for Matrix {
+    const NAME: &'static str = "Matrix";
+    const COMPONENTS: &'static [&'static str] = &[];
+
+    fn component(&self, index: usize) -> f32 {
+        self.to_cols_array()[index]
+    }
+
+    fn set_component(&mut self, index: usize, value: f32) {
+        let mut elements = self.to_cols_array();
+        elements[index] = value;
+        *self = Matrix::from_cols_array(&elements);
+    }
+
+    fn elements(&self) -> Vec<f32> {
+        self.to_cols_array().to_vec()
+    }
+
+    fn constructor_signature() -> String {
+        "(...elements: number[])".to_owned()
+    }
+
+    /// `new Math.Matrix()` is identity, otherwise takes 16 numbers in column major order.
+    fn construct(args: &[JsValue], context: &mut Context) -> JsResult<Self> {
+        match args.len() {
+            0 => Ok(Matrix::IDENTITY),
+            16 => Ok(Matrix::from_cols_array(&component_args::<16>(args, 0.0, context)?)),
+            n => Err(JsNativeError::range()
+                .with_message(format!("Math.Matrix takes 0 or 16 numbers, got {}", n))
+                .into()),
+        }
+    }
+
+    fn init(class: &mut DeclaredClass<'_, '_>) {
+        let methods: [(&str, NativeFn, &str); 8] = [
+            ("get", matrix_get, "(column: number, row: number): number"),
+            ("set", matrix_set, "(column: number, row: number, value: number): void"),
+            ("mul", matrix_mul, "<T extends Matrix | Vector4>(other: T): T"),
+            ("transformPoint", matrix_transform_point, "(point: Vector3): Vector3"),
+            ("transformVector", matrix_transform_vector, "(vector: Vector3): Vector3"),
+            ("inverse", matrix_inverse, "(): Matrix"),
+            ("transpose", matrix_transpose, "(): Matrix"),
+            ("determinant", matrix_determinant, "(): number"),
+        ];
+        for (name, function, signature) in methods.iter() {
+            class.method(name, NativeFunction::from_fn_ptr(*function), signature);
+        }
+        let static_methods: [(&str, NativeFn, &str); 5] = [
+            ("identity", matrix_identity, "(): Matrix"),
+            (
+                "fromTranslation",
+                matrix_from_translation,
+                "(translation: Vector3): Matrix",
+            ),
+            ("fromRotation", matrix_from_rotation, "(rotation: Quat): Matrix"),
+            ("fromScale", matrix_from_scale, "(scale: Vector3): Matrix"),
+            (
+                "fromScaleRotationTranslation",
+                matrix_from_scale_rotation_translation,
+                "(scale: Vector3, rotation: Quat, translation: Vector3): Matrix",
+            ),
+        ];
+        for (name, function, signature) in static_methods.iter() {
+            class.static_method(name, NativeFunction::from_fn_ptr(*function), signature);
+        }
+    }
+}
+
+fn matrix_index(args: &[JsValue], context: &mut Context) -> JsResult<usize> {
+    let column = args.get_or_undefined(0).to_number(context)?;
+    let row = args.get_or_undefined(1).to_number(context)?;
+    let valid = |i: f64| (0.0..4.0).contains(&i) && i.fract() == 0.0;
+    if !valid(column) || !valid(row) {
+        return Err(JsNativeError::range()
+            .with_message(format!("matrix index ({}, {}) out of range", column, row))
+            .into());
+    }
+    Ok(column as usize * 4 + row as usize)
+}
+
+fn matrix_get(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<Matrix>(this)?;
+    let index = matrix_index(args, context)?;
+    Ok(value.component(index).into())
+}
+
+fn matrix_set(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let object = this_object::<Matrix>(this)?;
+    let index = matrix_index(args, context)?;
+    let element = number_arg(args, 2, context)?;
+    object
+        .downcast_mut::<MathObject<Matrix>>()
+        .unwrap()
+        .value
+        .set_component(index, element);
+    Ok(JsValue::undefined())
+}
+
+/// Multiplies matrices for `Math.Matrix` argument, transforms vector for `Math.Vector4` argument.
+fn matrix_mul(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<Matrix>(this)?;
+    let arg = args.get_or_undefined(0);
+    if let Some(vector) = arg
+        .as_object()
+        .and_then(|o| o.downcast_ref::<MathObject<Vec4>>().map(|v| v.value))
+    {
+        return Ok(to_js(context, value * vector));
+    }
+    let other = from_js::<Matrix>(arg, context)?;
+    Ok(to_js(context, value * other))
+}
+
+fn matrix_transform_point(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<Matrix>(this)?;
+    let point = from_js::<Vec3>(args.get_or_undefined(0), context)?;
+    Ok(to_js(context, value.transform_point3(point)))
+}
+
+fn matrix_transform_vector(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<Matrix>(this)?;
+    let vector = from_js::<Vec3>(args.get_or_undefined(0), context)?;
+    Ok(to_js(context, value.transform_vector3(vector)))
+}
+
+fn matrix_inverse(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<Matrix>(this)?;
+    Ok(to_js(context, value.inverse()))
+}
+
+fn matrix_transpose(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = this_value::<Matrix>(this)?;
+    Ok(to_js(context, value.transpose()))
+}
+
+fn matrix_determinant(this: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+    Ok(this_value::<Matrix>(this)?.determinant().into())
+}
+
+fn matrix_identity(_this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    Ok(to_js(context, Matrix::IDENTITY))
+}
+
+fn matrix_from_translation(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let translation = from_js::<Vec3>(args.get_or_undefined(0), context)?;
+    Ok(to_js(context, Matrix::from_translation(translation)))
+}
+
+fn matrix_from_rotation(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let rotation = from_js::<Quat>(args.get_or_undefined(0), context)?;
+    Ok(to_js(context, Matrix::from_quat(rotation)))
+}
+
+fn matrix_from_scale(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let scale = from_js::<Vec3>(args.get_or_undefined(0), context)?;
+    Ok(to_js(context, Matrix::from_scale(scale)))
+}
+
+fn matrix_from_scale_rotation_translation(
+    _this: &JsValue,
+    args: &[JsValue],
+    context: &mut Context,
+) -> JsResult<JsValue> {
+    let scale = from_js::<Vec3>(args.get_or_undefined(0), context)?;
+    let rotation = from_js::<Quat>(args.get_or_undefined(1), context)?;
+    let translation = from_js::<Vec3>(args.get_or_undefined(2), context)?;
+    Ok(to_js(
+        context,
+        Matrix::from_scale_rotation_translation(scale, rotation, translation),
+    ))
+}
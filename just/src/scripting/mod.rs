@@ -0,0 +1,596 @@
+mod camera;
+mod console;
+mod controller;
+mod events;
+mod game_object;
+mod input;
+mod limits;
+pub mod math;
+pub mod reload;
+mod resources;
+mod time;
+pub mod timers;
+mod tokens;
+mod typings;
+mod world;
+
+pub use controller::ScriptController;
+pub use game_object::GameObjectHandle;
+pub use limits::{ScriptAllocator, ScriptLimits};
+
+use boa_engine::{js_string, Context, JsError, JsNativeError, JsObject, JsResult, JsValue, Script, Source};
+use just_core::ecs::prelude::*;
+use just_core::events::EventBus;
+use just_core::game_object::GameObject;
+use just_core::hierarchy::{HierarchyChannel, HierarchyEvent};
+use just_core::shrev::ReaderId;
+use just_input::{InputChannel, InputEvent, InputReader};
+use just_wgpu::{ConsoleEntry, Ui};
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::core::TimeData;
+use crate::scene::ScriptName;
+use console::ScriptSources;
+use controller::{Controllers, Hook};
+use reload::{ScriptChange, ScriptWatcher};
+
+pub const ENTRY_SCRIPT: &str = "index.js";
+
+thread_local! {
+    static WORLD: Cell<*mut World> = Cell::new(std::ptr::null_mut());
+}
+
+/// Gives native bindings access to the world. Only available while `ScriptSystem` runs scripts.
+/// Bindings must not call back into JS from inside of `f`.
+pub(crate) fn with_world<R, F: FnOnce(&mut World) -> R>(f: F) -> JsResult<R> {
+    let world = WORLD.with(|w| w.replace(std::ptr::null_mut()));
+    if world.is_null() {
+        return Err(JsNativeError::error()
+            .with_message("world is not accessible at this point")
+            .into());
+    }
+    // pointer is taken out for duration of the call, so nested calls can't alias it
+    // and engine allocations made by bindings don't count as script memory
+    let result = limits::set_counting(false, || f(unsafe { &mut *world }));
+    WORLD.with(|w| w.set(world));
+    Ok(result)
+}
+
+/// Runs scripts with access to the world. They get a budget of `ScriptLimits`,
+/// hooks of controllers get their own in `call_hook_only`.
+fn enter_world<R, F: FnOnce() -> R>(world: &mut World, f: F) -> R {
+    let script_limits = *world.resources.get::<ScriptLimits>().unwrap();
+    let previous = WORLD.with(|w| w.replace(world as *mut World));
+    let result = limits::set_counting(true, || limits::run_hook(&script_limits, None, f));
+    WORLD.with(|w| w.set(previous));
+    result
+}
+
+pub struct ScriptSystem {
+    context: Context,
+    hierarchy_reader: ReaderId<HierarchyEvent>,
+    input_reader: InputReader,
+    watcher: ScriptWatcher,
+    sources: ScriptSources,
+}
+
+impl ScriptSystem {
+    /// Evaluates every script in `scripts_path`, entry script (`index.js`) being the last one.
+    /// Script errors and `console` output go to `Console` of the `Ui` and are printed.
+    /// Scripts run within `ScriptLimits` found in world resources, defaults are inserted if there are none.
+    /// Same goes for `EventBus` shared with Rust systems.
+    pub fn initialize(world: &mut World, scripts_path: &str) -> Self {
+        println!(
+            "Script system: Loading scripts from: {}",
+            std::fs::canonicalize(scripts_path).unwrap().display()
+        );
+        let mut context = Context::builder().host_hooks(&limits::ScriptHooks).build().unwrap();
+        Self::register_bindings(&mut context);
+        if world.resources.get::<ScriptLimits>().is_none() {
+            world.resources.insert(ScriptLimits::default());
+        }
+        world.resources.get::<ScriptLimits>().unwrap().apply(&mut context);
+        if world.resources.get::<EventBus>().is_none() {
+            world.resources.insert(EventBus::default());
+        }
+
+        let hierarchy_reader = world.resources.get_mut::<HierarchyChannel>().unwrap().register_reader();
+        let input_reader = world.resources.get_mut::<InputChannel>().unwrap().register_reader();
+
+        let mut files: Vec<PathBuf> = std::fs::read_dir(scripts_path)
+            .map_err(|_| "couldn't read directory")
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().map(|ext| ext == "js").unwrap_or(false))
+            .collect();
+        files.sort_by_key(|path| (path.file_name().unwrap() == ENTRY_SCRIPT, path.clone()));
+
+        let mut system = ScriptSystem {
+            context,
+            hierarchy_reader,
+            input_reader,
+            watcher: ScriptWatcher::new(Path::new(scripts_path), &files),
+            sources: ScriptSources::default(),
+        };
+
+        for file in files.iter() {
+            system.load_file(world, file);
+        }
+        enter_world(world, || system.context.run_jobs());
+        system
+    }
+
+    fn register_bindings(context: &mut Context) {
+        console::register(context);
+        limits::register(context);
+        math::register(context).unwrap();
+        input::register(context);
+        world::register(context);
+        camera::register(context);
+        time::register(context);
+        resources::register(context);
+        timers::register(context);
+        events::register(context);
+        context.register_global_class::<GameObjectHandle>().unwrap();
+        Controllers::install(context);
+    }
+
+    /// TypeScript declarations (`just.d.ts`) of everything scripts can access.
+    /// They're collected while bindings are registered, so they always match them.
+    pub fn typings() -> String {
+        let mut context = Context::default();
+        Self::register_bindings(&mut context);
+        typings::render(&context)
+    }
+
+    /// Evaluates whole file, errors are reported.
+    fn load_file(&mut self, world: &mut World, path: &Path) -> bool {
+        let name = file_name(path);
+        let source = match std::fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(error) => {
+                console::report(world, ConsoleEntry::error(Some(name), error.to_string()));
+                return false;
+            }
+        };
+        console::set_source(&mut self.context, Some(name.clone()));
+        let instrumented = limits::instrument(&source);
+        let result = enter_world(world, || self.context.eval(Source::from_bytes(&instrumented)));
+        console::set_source(&mut self.context, None);
+        self.sources.insert(name.clone(), source);
+        match result {
+            Ok(_) => true,
+            Err(error) => {
+                console::report(
+                    world,
+                    console::error_entry(&error, Some(name.clone()), Some(&name), None),
+                );
+                false
+            }
+        }
+    }
+
+    /// Reloads scripts changed on disk. New files are evaluated like on startup. In modified files
+    /// only top level classes are evaluated again, so objects created by the script are not duplicated.
+    /// Reloaded classes replace old ones and live controllers get new prototypes, keeping their fields.
+    /// Controllers disabled for breaking `ScriptLimits` are enabled again when their class is reloaded.
+    /// Script that fails to compile or throws leaves previous version running.
+    fn reload_changed(&mut self, world: &mut World) {
+        for change in self.watcher.changes() {
+            match change {
+                ScriptChange::Added(path) => {
+                    if self.load_file(world, &path) {
+                        let entry = ConsoleEntry::info(Some(file_name(&path)), "loaded".to_owned());
+                        console::report(world, entry);
+                    }
+                }
+                ScriptChange::Modified(path) => {
+                    let name = file_name(&path);
+                    let source = match std::fs::read_to_string(&path) {
+                        Ok(source) => source,
+                        Err(error) => {
+                            console::report(world, ConsoleEntry::error(Some(name), error.to_string()));
+                            continue;
+                        }
+                    };
+                    match enter_world(world, || self.reload_classes(&source)) {
+                        Ok(classes) => {
+                            self.sources.insert(name.clone(), source);
+                            let message = format!("reloaded [{}]", classes.join(", "));
+                            console::report(world, ConsoleEntry::info(Some(name), message));
+                        }
+                        Err(error) => {
+                            let mut entry = console::error_entry(&error, Some(name.clone()), Some(&name), None);
+                            entry.message = format!("couldn't reload, keeping previous version: {}", entry.message);
+                            console::report(world, entry);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Evaluates class declarations from `source` again and swaps them in, returns their names.
+    fn reload_classes(&mut self, source: &str) -> JsResult<Vec<String>> {
+        // whole file has to compile, even though only classes are evaluated
+        Script::parse(Source::from_bytes(source), None, &mut self.context)?;
+        let classes = reload::class_declarations(source);
+        if classes.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // redeclaring classes at top level is a syntax error, so they're created in function scope
+        let mut wrapper = String::from("(function () {\n");
+        for class in classes.iter() {
+            // assigned classes are anonymous, binding gives them the name
+            wrapper.push_str(&format!("let {} = {};\n", class.name, class.text));
+        }
+        wrapper.push_str("return {");
+        for class in classes.iter() {
+            wrapper.push_str(&format!("{0}: {0}, ", class.name));
+        }
+        wrapper.push_str("};\n})()");
+        let fresh = self.context.eval(Source::from_bytes(&limits::instrument(&wrapper)))?;
+        let fresh = fresh.as_object().cloned().unwrap();
+
+        let mut prototypes: Vec<(JsObject, JsObject)> = vec![];
+        let mut rebind = String::from("(function (classes) {\n");
+        for name in classes.iter().map(|class| &class.name) {
+            let class = fresh.get(js_string!(name.as_str()), &mut self.context)?;
+            let prototype = class
+                .as_object()
+                .unwrap()
+                .get(js_string!("prototype"), &mut self.context)?;
+            // class may be new in this version of the file
+            let previous = self.context.eval(Source::from_bytes(&format!(
+                "typeof {0} === 'function' ? {0} : undefined",
+                name
+            )))?;
+            if let Some(previous) = previous.as_object() {
+                let previous = previous.get(js_string!("prototype"), &mut self.context)?;
+                if let (Some(previous), Some(prototype)) = (previous.as_object(), prototype.as_object()) {
+                    prototypes.push((previous.clone(), prototype.clone()));
+                }
+            }
+            rebind.push_str(&format!("{0} = classes.{0};\n", name));
+        }
+        rebind.push_str("})");
+        let rebind = self.context.eval(Source::from_bytes(&rebind))?;
+        rebind
+            .as_callable()
+            .unwrap()
+            .call(&JsValue::undefined(), &[fresh.into()], &mut self.context)?;
+
+        // prototype chains are walked, so that subclasses declared in other files pick up changes too
+        for (controller_id, controller) in Controllers::objects(&self.context) {
+            let mut object = controller.clone();
+            let mut reloaded = false;
+            while let Some(prototype) = object.prototype() {
+                let replacement = prototypes
+                    .iter()
+                    .find(|(previous, _)| JsObject::equals(previous, &prototype))
+                    .map(|(_, replacement)| replacement.clone());
+                // frozen objects keep old prototype
+                object = match replacement {
+                    Some(replacement) if object.set_prototype(Some(replacement.clone())) => {
+                        reloaded = true;
+                        replacement
+                    }
+                    _ => prototype,
+                };
+            }
+            if reloaded && Controllers::enable(&mut self.context, controller_id) {
+                let label = format!("{}#{}", self.controller_class(&controller), controller_id);
+                with_world(|world| {
+                    console::report(world, ConsoleEntry::info(Some(label), "enabled again".to_owned()))
+                })?;
+            }
+        }
+        Ok(classes.into_iter().map(|class| class.name).collect())
+    }
+
+    /// Makes `controller` control game object `id`, replacing its previous controller.
+    /// Controller gets `go` property and its `start()` is called before its first `update(dt)`.
+    pub fn attach(&mut self, world: &mut World, id: Entity, controller: JsObject) -> JsResult<()> {
+        if let Some(previous) = world.get_component::<ScriptController>(id).map(|c| *c) {
+            Controllers::remove(&mut self.context, previous.id);
+        }
+        let component = Controllers::register(&mut self.context, id, controller)?;
+        world.add_component(id, component);
+        Ok(())
+    }
+
+    /// Reloads changed scripts, creates controllers for new scripted objects, delivers input events
+    /// and events of `EventBus` to subscribed callbacks, calls `start()` and `update(dt)` on all enabled controllers
+    /// of active objects and then runs timers that are due.
+    pub fn update(&mut self, world: &mut World) {
+        world.resources.get::<ScriptLimits>().unwrap().apply(&mut self.context);
+        self.reload_changed(world);
+        self.dispatch_hierarchy_events(world);
+
+        let requested: Vec<(Entity, String)> = Read::<ScriptName>::query()
+            .iter_entities_immutable(world)
+            .map(|(id, script)| (id, script.0.clone()))
+            .collect();
+        for (id, script) in requested {
+            if world.get_component::<ScriptController>(id).is_some() {
+                continue;
+            }
+            let controller = enter_world(world, || {
+                let controller = self.construct(&script);
+                self.context.run_jobs();
+                controller
+            });
+            match controller.and_then(|controller| self.attach(world, id, controller)) {
+                Ok(()) => (),
+                Err(error) => {
+                    let location = self.sources.locate(&script, Some("constructor"));
+                    let frame = (format!("new {}", script), location);
+                    let mut entry = console::error_entry(&error, Some(script.clone()), None, Some(frame));
+                    entry.message = format!("couldn't create controller: {}", entry.message);
+                    console::report(world, entry);
+                }
+            }
+        }
+
+        // events consumed by UI (e.g. clicks on windows) are not passed to scripts
+        let input_events: Vec<InputEvent> = {
+            let (ui, channel) = <(Read<Ui>, Read<InputChannel>)>::fetch(&world.resources);
+            ui.filter_input(channel.read(&mut self.input_reader)).copied().collect()
+        };
+        for owner in events::owners(&self.context) {
+            if !world.is_alive(owner) {
+                events::cancel_owned(&mut self.context, owner);
+            }
+        }
+        let context = &mut self.context;
+        enter_world(world, || {
+            input::dispatch(context, &input_events);
+            events::dispatch(context);
+            context.run_jobs();
+        });
+        self.dispatch_hierarchy_events(world);
+
+        let dt = world.resources.get::<TimeData>().unwrap().dt();
+        for (controller_id, entity) in Controllers::all(&self.context) {
+            // objects removed without `GameObject::remove_marked`, like children of rebuilt prefab instances
+            if !world.is_alive(entity) {
+                Controllers::remove(&mut self.context, controller_id);
+                continue;
+            }
+            // `start()` is delayed until object becomes active
+            if !GameObject::is_active_in_hierarchy(world, entity).unwrap_or(false) {
+                continue;
+            }
+            // might have been removed, replaced or disabled by previous controller
+            let controller = match Controllers::get(&self.context, controller_id) {
+                Some(controller) => controller,
+                None => continue,
+            };
+            if Controllers::start(&mut self.context, controller_id) {
+                self.call_hook(world, controller_id, &controller, Hook::START.name, &[]);
+            }
+            // `start()` might have disabled it
+            if Controllers::get(&self.context, controller_id).is_some() {
+                let args = [JsValue::from(dt)];
+                self.call_hook(world, controller_id, &controller, Hook::UPDATE.name, &args);
+            }
+        }
+
+        self.run_timers(world);
+    }
+
+    /// Calls timer callbacks and resolves `wait`/`nextFrame` promises that are due.
+    /// Timers of destroyed objects are cancelled, ones of disabled controllers and inactive objects wait
+    /// until they're enabled again.
+    fn run_timers(&mut self, world: &mut World) {
+        // code awaiting promises is continued by whoever resolved them, so none may be left from before
+        enter_world(world, || self.context.run_jobs());
+        for owner in timers::owners(&self.context) {
+            if !world.is_alive(owner) {
+                timers::cancel_owned(&mut self.context, owner);
+            }
+        }
+        let (now, frame) = {
+            let time = world.resources.get::<TimeData>().unwrap();
+            (time.elapsed() as f64, time.frame_count())
+        };
+        let mut paused: Vec<Entity> = Controllers::disabled(&self.context)
+            .into_iter()
+            .map(|(_, entity, _)| entity)
+            .collect();
+        for owner in timers::owners(&self.context) {
+            if !GameObject::is_active_in_hierarchy(world, owner).unwrap_or(true) {
+                paused.push(owner);
+            }
+        }
+        let limits = *world.resources.get::<ScriptLimits>().unwrap();
+
+        for timer in timers::take_due(&mut self.context, now, frame, &paused) {
+            let controller = timer
+                .owner
+                .and_then(|owner| world.get_component::<ScriptController>(owner).map(|c| c.id));
+            let label = match controller.and_then(|id| Some((id, Controllers::get(&self.context, id)?))) {
+                Some((id, object)) => format!("{}#{}", self.controller_class(&object), id),
+                None => "timer".to_owned(),
+            };
+            console::set_source(&mut self.context, Some(label.clone()));
+            let previous_owner = timers::set_owner(&mut self.context, timer.owner);
+            let context = &mut self.context;
+            let started = Instant::now();
+            let result = enter_world(world, || {
+                limits::run_hook(&limits, controller, || {
+                    let result = timer.callback.call(&JsValue::undefined(), &timer.args, context);
+                    // code awaiting resolved promise continues right away, so it's limited like the callback
+                    // and timers it creates have the same owner
+                    context.run_jobs();
+                    result
+                })
+            });
+            let elapsed = started.elapsed();
+            timers::set_owner(&mut self.context, previous_owner);
+            console::set_source(&mut self.context, None);
+
+            if let Some(reason) = limits.check(&result, elapsed, controller) {
+                let message = match controller {
+                    Some(id) => {
+                        Controllers::disable(&mut self.context, id, reason.clone());
+                        format!("disabled in timer: {}", reason)
+                    }
+                    None => {
+                        timers::cancel(&mut self.context, timer.id);
+                        format!("timer cancelled: {}", reason)
+                    }
+                };
+                console::report(world, ConsoleEntry::error(Some(label), message));
+            } else if let Err(error) = result {
+                console::report(world, console::error_entry(&error, Some(label), None, None));
+            }
+            self.dispatch_hierarchy_events(world);
+        }
+    }
+
+    /// Controllers disabled for breaking `ScriptLimits`: controller id, its object and reason.
+    pub fn disabled_controllers(&self) -> Vec<(u32, Entity, String)> {
+        Controllers::disabled(&self.context)
+    }
+
+    /// Approximate number of bytes allocated by scripts, only counted with `ScriptAllocator` as global allocator.
+    pub fn memory_usage(&self) -> usize {
+        limits::script_memory()
+    }
+
+    /// Calls `onDestroy()` of object's controller and forgets the controller, cancelling its timers
+    /// and event subscriptions. Ones created by `onDestroy()` itself keep running.
+    /// Called by engine right before object is removed.
+    pub fn on_destroy(&mut self, world: &mut World, id: Entity) {
+        timers::cancel_owned(&mut self.context, id);
+        events::cancel_owned(&mut self.context, id);
+        let component = match world.get_component::<ScriptController>(id).map(|c| *c) {
+            Some(component) => component,
+            None => return,
+        };
+        match Controllers::remove(&mut self.context, component.id) {
+            Some(controller) if controller.disabled.is_none() => {
+                self.call_hook(world, component.id, &controller.object, Hook::ON_DESTROY.name, &[])
+            }
+            _ => (),
+        }
+    }
+
+    /// Calls `onParentChanged(newParent, oldParent)` of controllers whose objects were reparented
+    /// since last call. Scripts get it right after the hook that reparented the object,
+    /// changes made by engine systems are delivered by `Engine` at the sync point following the system.
+    pub fn dispatch_hierarchy_events(&mut self, world: &mut World) {
+        loop {
+            let events: Vec<HierarchyEvent> = world
+                .resources
+                .get::<HierarchyChannel>()
+                .unwrap()
+                .read(&mut self.hierarchy_reader)
+                .copied()
+                .collect();
+            if events.is_empty() {
+                return;
+            }
+            for event in events {
+                let HierarchyEvent::ParentChanged {
+                    id,
+                    old_parent,
+                    new_parent,
+                } = event;
+                if !world.is_alive(id) {
+                    continue;
+                }
+                let component = match world.get_component::<ScriptController>(id).map(|c| *c) {
+                    Some(component) => component,
+                    None => continue,
+                };
+                if let Some(controller) = Controllers::get(&self.context, component.id) {
+                    let new_parent = game_object::handle_or_null(&mut self.context, new_parent);
+                    let old_parent = game_object::handle_or_null(&mut self.context, old_parent);
+                    let args = [new_parent, old_parent];
+                    let name = Hook::ON_PARENT_CHANGED.name;
+                    self.call_hook_only(world, component.id, &controller, name, &args);
+                }
+            }
+        }
+    }
+
+    /// Calls hook and then delivers parent changes it caused.
+    fn call_hook(&mut self, world: &mut World, id: u32, controller: &JsObject, name: &str, args: &[JsValue]) {
+        self.call_hook_only(world, id, controller, name, args);
+        self.dispatch_hierarchy_events(world);
+    }
+
+    /// Calls hook, controller `id` is disabled if it breaks `ScriptLimits`.
+    /// Errors and `console` output are reported with controller as their source.
+    fn call_hook_only(&mut self, world: &mut World, id: u32, controller: &JsObject, name: &str, args: &[JsValue]) {
+        let limits = *world.resources.get::<ScriptLimits>().unwrap();
+        let class = self.controller_class(controller);
+        // e.g. `test2#3`
+        let label = format!("{}#{}", class, id);
+        console::set_source(&mut self.context, Some(label.clone()));
+        let owner = Controllers::entity(&self.context, id);
+        let previous_owner = timers::set_owner(&mut self.context, owner);
+        let context = &mut self.context;
+        let started = Instant::now();
+        let result = enter_world(world, || {
+            limits::run_hook(&limits, Some(id), || {
+                let result = Self::call_method(context, controller, name, args);
+                // code awaiting promises resolved by the hook continues right away, so it's limited like the hook
+                context.run_jobs();
+                result
+            })
+        });
+        let elapsed = started.elapsed();
+        timers::set_owner(&mut self.context, previous_owner);
+        console::set_source(&mut self.context, None);
+
+        if let Some(reason) = limits.check(&result, elapsed, Some(id)) {
+            Controllers::disable(&mut self.context, id, reason.clone());
+            let message = format!("disabled in {}: {}", name, reason);
+            console::report(world, ConsoleEntry::error(Some(label), message));
+        } else if let Err(error) = result {
+            let location = self.sources.locate(&class, Some(name));
+            let frame = (format!("{}.{}", class, name), location);
+            console::report(world, console::error_entry(&error, Some(label), None, Some(frame)));
+        }
+    }
+
+    fn controller_class(&mut self, controller: &JsObject) -> String {
+        console::class_name(&mut self.context, controller).unwrap_or_else(|| "Object".to_owned())
+    }
+
+    /// Creates instance of a class declared by scripts.
+    fn construct(&mut self, class: &str) -> JsResult<JsObject> {
+        let valid_name = class.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+            && !class.starts_with(|c: char| c.is_ascii_digit());
+        if class.is_empty() || !valid_name {
+            return Err(JsNativeError::syntax()
+                .with_message(format!("'{}' is not a valid class name", class))
+                .into());
+        }
+        // class declarations are not properties of global object, so name is resolved by evaluating it
+        let constructor = self.context.eval(Source::from_bytes(class))?;
+        let constructor = constructor
+            .as_constructor()
+            .ok_or_else(|| JsError::from(JsNativeError::typ().with_message(format!("{} is not a class", class))))?;
+        constructor.construct(&[], None, &mut self.context)
+    }
+
+    /// Calls method if object has it, missing methods are skipped.
+    fn call_method(context: &mut Context, object: &JsObject, name: &str, args: &[JsValue]) -> JsResult<JsValue> {
+        let method = object.get(js_string!(name), context)?;
+        match method.as_callable() {
+            Some(method) => method.call(&JsValue::from(object.clone()), args, context),
+            None => Ok(JsValue::undefined()),
+        }
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap().to_string_lossy().into_owned()
+}
@@ -0,0 +1,249 @@
+use boa_ast::declaration::{Binding, VarDeclaration, Variable};
+use boa_ast::expression::operator::assign::{AssignOp, AssignTarget};
+use boa_ast::expression::Identifier;
+use boa_ast::{Declaration, Expression, Statement, StatementListItem};
+use boa_interner::Interner;
+use boa_parser::{Parser, Source};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use super::tokens::{tokenize, Token, TokenKind};
+
+const CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+pub enum ScriptChange {
+    Added(PathBuf),
+    Modified(PathBuf),
+}
+
+/// Polls modification times of `.js` files in scripts directory.
+pub struct ScriptWatcher {
+    directory: PathBuf,
+    modified: HashMap<PathBuf, SystemTime>,
+    last_check: Instant,
+}
+
+impl ScriptWatcher {
+    pub fn new(directory: &Path, files: &[PathBuf]) -> Self {
+        let modified = files
+            .iter()
+            .filter_map(|file| Some((file.clone(), modification_time(file)?)))
+            .collect();
+        ScriptWatcher {
+            directory: directory.to_owned(),
+            modified,
+            last_check: Instant::now(),
+        }
+    }
+
+    /// Files added or modified since last call, checked at most every `CHECK_INTERVAL`.
+    pub fn changes(&mut self) -> Vec<ScriptChange> {
+        if self.last_check.elapsed() < CHECK_INTERVAL {
+            return vec![];
+        }
+        self.last_check = Instant::now();
+        let entries = match std::fs::read_dir(&self.directory) {
+            Ok(entries) => entries,
+            Err(_) => return vec![],
+        };
+        let mut changes = vec![];
+        for path in entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+            if path.extension().map(|ext| ext != "js").unwrap_or(true) {
+                continue;
+            }
+            // file may be in the middle of being saved, it'll be picked up by next check
+            let time = match modification_time(&path) {
+                Some(time) => time,
+                None => continue,
+            };
+            match self.modified.insert(path.clone(), time) {
+                None => changes.push(ScriptChange::Added(path)),
+                Some(previous) if previous != time => changes.push(ScriptChange::Modified(path)),
+                Some(_) => (),
+            }
+        }
+        changes
+    }
+}
+
+fn modification_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+pub struct ClassDeclaration {
+    pub name: String,
+    /// Source of the class, `class X {}` or `class {}` if it is assigned.
+    pub text: String,
+    /// Byte offset of `class` keyword in script.
+    pub start: usize,
+}
+
+/// Finds classes declared at top level of script, as `class X {}` or assigned with `X = class {}`
+/// (also with `var`, `let` or `const`). Script is parsed to tell which classes there are,
+/// their source is then found among its tokens. Scripts with syntax errors have no classes.
+pub fn class_declarations(source: &str) -> Vec<ClassDeclaration> {
+    let mut interner = Interner::default();
+    let script = match Parser::new(Source::from_bytes(source)).parse_script(&mut interner) {
+        Ok(script) => script,
+        Err(_) => return vec![],
+    };
+    let names: Vec<String> = script
+        .statements()
+        .statements()
+        .iter()
+        .filter_map(top_level_class)
+        .map(|name| interner.resolve_expect(name.sym()).to_string())
+        .collect();
+
+    let tokens = tokenize(source);
+    let candidates = class_candidates(&tokens);
+    let mut next = 0;
+    let mut classes = vec![];
+    for name in names {
+        let found = candidates[next..].iter().position(|(candidate, ..)| *candidate == name);
+        let (start, end) = match found {
+            Some(i) => {
+                let (_, start, end) = candidates[next + i];
+                next += i + 1;
+                (start, end)
+            }
+            None => continue,
+        };
+        let text = &source[start..end];
+        // tokens are split without full grammar, so the result is checked to be the whole class
+        let expression = format!("({})", text);
+        if Parser::new(Source::from_bytes(&expression))
+            .parse_script(&mut interner)
+            .is_err()
+        {
+            continue;
+        }
+        classes.push(ClassDeclaration {
+            name,
+            text: text.to_owned(),
+            start,
+        });
+    }
+    classes
+}
+
+/// Name of class declared or assigned by top level statement.
+fn top_level_class(item: &StatementListItem) -> Option<Identifier> {
+    match item {
+        StatementListItem::Declaration(Declaration::Class(class)) => class.name(),
+        StatementListItem::Declaration(Declaration::Lexical(declaration)) => {
+            assigned_class(declaration.variable_list().as_ref())
+        }
+        StatementListItem::Statement(Statement::Var(VarDeclaration(list))) => assigned_class(list.as_ref()),
+        StatementListItem::Statement(Statement::Expression(Expression::Assign(assign))) => {
+            match (assign.op(), assign.lhs(), assign.rhs()) {
+                (AssignOp::Assign, AssignTarget::Identifier(name), Expression::Class(_)) => Some(*name),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn assigned_class(variables: &[Variable]) -> Option<Identifier> {
+    match variables {
+        [variable] => match (variable.binding(), variable.init()) {
+            (Binding::Identifier(name), Some(Expression::Class(_))) => Some(*name),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Byte offset of method `name` in class source, looks only at class body level.
+pub fn method_offset(class: &str, name: &str) -> Option<usize> {
+    let tokens = tokenize(class);
+    let mut depth = 0usize;
+    let mut body = None;
+    for (i, token) in tokens.iter().enumerate() {
+        match token.kind {
+            TokenKind::Open => {
+                // `extends` clause may contain calls
+                if depth == 0 && token.text == "{" {
+                    body = Some(depth + 1);
+                }
+                depth += 1;
+            }
+            TokenKind::Close => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 && body.is_some() {
+                    return None;
+                }
+            }
+            TokenKind::Word if body == Some(depth) && token.text == name => {
+                let called = tokens.get(i + 1).map(|next| next.text == "(").unwrap_or(false);
+                // not a call in field initializer or a property access
+                let initializer = i > 0 && matches!(tokens[i - 1].text, "=" | ".");
+                if called && !initializer {
+                    return Some(token.start);
+                }
+            }
+            _ => (),
+        }
+    }
+    None
+}
+
+/// One based line and column of byte `offset` in `source`.
+pub fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+    (line, column)
+}
+
+/// Names, starts and ends of everything at top level looking like class declaration or assignment.
+fn class_candidates(tokens: &[Token]) -> Vec<(String, usize, usize)> {
+    let mut candidates = vec![];
+    let mut depth = 0usize;
+    for (i, token) in tokens.iter().enumerate() {
+        match token.kind {
+            TokenKind::Open => depth += 1,
+            TokenKind::Close => depth = depth.saturating_sub(1),
+            TokenKind::Word if depth == 0 && token.text == "class" => {
+                let assigned = i >= 2 && tokens[i - 1].text == "=" && tokens[i - 2].kind == TokenKind::Word;
+                let name = if assigned {
+                    tokens[i - 2].text
+                } else {
+                    match tokens.get(i + 1) {
+                        Some(next) if next.kind == TokenKind::Word && next.text != "extends" => next.text,
+                        _ => continue,
+                    }
+                };
+                if let Some(end) = class_end(&tokens[i..]) {
+                    candidates.push((name.to_owned(), token.start, end));
+                }
+            }
+            _ => (),
+        }
+    }
+    candidates
+}
+
+/// End of class whose `class` keyword is the first token.
+fn class_end(tokens: &[Token]) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_body = false;
+    for token in tokens {
+        match token.kind {
+            TokenKind::Open => {
+                in_body |= depth == 0 && token.text == "{";
+                depth += 1;
+            }
+            TokenKind::Close => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 && in_body {
+                    return Some(token.start + 1);
+                }
+            }
+            _ => (),
+        }
+    }
+    None
+}
@@ -0,0 +1,203 @@
+use boa_engine::class::{Class, ClassBuilder};
+use boa_engine::object::builtins::JsArray;
+use boa_engine::{js_string, Context, JsArgs, JsData, JsNativeError, JsResult, JsValue, NativeFunction};
+use boa_gc::{Finalize, Trace};
+use just_assets::{AssetState, AssetStorage};
+use just_core::ecs::prelude::*;
+use just_wgpu::{Mesh, Texture};
+
+use super::typings::{DeclaredClass, GlobalObject};
+use super::with_world;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AssetKind {
+    Mesh,
+    Texture,
+}
+
+impl AssetKind {
+    fn name(self) -> &'static str {
+        match self {
+            AssetKind::Mesh => "mesh",
+            AssetKind::Texture => "texture",
+        }
+    }
+
+    /// Handle id and state of named asset.
+    fn lookup(self, world: &World, name: &str) -> Option<(usize, &'static str)> {
+        fn find<T>(storage: &AssetStorage<T>, name: &str) -> Option<(usize, &'static str)> {
+            let handle = storage.get_handle(name)?;
+            let state = match storage.get_state(&handle)? {
+                AssetState::Offline => "Offline",
+                AssetState::Queued => "Queued",
+                AssetState::Loaded(_) => "Loaded",
+            };
+            Some((handle.id(), state))
+        }
+        match self {
+            AssetKind::Mesh => find(&world.resources.get::<AssetStorage<Mesh>>().unwrap(), name),
+            AssetKind::Texture => find(&world.resources.get::<AssetStorage<Texture>>().unwrap(), name),
+        }
+    }
+
+    fn names(self, world: &World) -> Vec<String> {
+        let mut names: Vec<String> = match self {
+            AssetKind::Mesh => {
+                let storage = world.resources.get::<AssetStorage<Mesh>>().unwrap();
+                storage.names().map(str::to_owned).collect()
+            }
+            AssetKind::Texture => {
+                let storage = world.resources.get::<AssetStorage<Texture>>().unwrap();
+                storage.names().map(str::to_owned).collect()
+            }
+        };
+        names.sort();
+        names
+    }
+}
+
+/// Script side handle to mesh or texture, resolved by name.
+/// `state` is read from asset storage every time, so it follows loading progress.
+#[derive(Debug, Clone, Trace, Finalize, JsData)]
+pub struct AssetHandle {
+    #[unsafe_ignore_trace]
+    kind: AssetKind,
+    name: String,
+    id: usize,
+}
+
+impl AssetHandle {
+    /// Resolves asset by name, `null` for unknown names.
+    pub fn resolve(context: &mut Context, kind: AssetKind, name: &str) -> JsResult<JsValue> {
+        let id = with_world(|world| kind.lookup(world, name).map(|(id, _)| id))?;
+        match id {
+            Some(id) => {
+                let handle = AssetHandle {
+                    kind,
+                    name: name.to_owned(),
+                    id,
+                };
+                Ok(Self::from_data(handle, context)?.into())
+            }
+            None => Ok(JsValue::null()),
+        }
+    }
+
+    /// Name of asset given either as handle of right kind or as a string.
+    pub fn name_arg(value: &JsValue, kind: AssetKind, context: &mut Context) -> JsResult<String> {
+        if let Some(object) = value.as_object() {
+            if let Some(handle) = object.downcast_ref::<AssetHandle>() {
+                if handle.kind != kind {
+                    return Err(JsNativeError::typ()
+                        .with_message(format!(
+                            "expected {}, got {} '{}'",
+                            kind.name(),
+                            handle.kind.name(),
+                            handle.name
+                        ))
+                        .into());
+                }
+                return Ok(handle.name.clone());
+            }
+        }
+        Ok(value.to_string(context)?.to_std_string_escaped())
+    }
+
+    fn this(this: &JsValue) -> JsResult<AssetHandle> {
+        this.as_object()
+            .and_then(|object| object.downcast_ref::<AssetHandle>().map(|handle| handle.clone()))
+            .ok_or_else(|| JsNativeError::typ().with_message("'this' is not an Asset").into())
+    }
+
+    fn get_name(this: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+        Ok(js_string!(Self::this(this)?.name.clone()).into())
+    }
+
+    fn get_kind(this: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+        Ok(js_string!(Self::this(this)?.kind.name()).into())
+    }
+
+    fn get_id(this: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+        Ok(JsValue::from(Self::this(this)?.id))
+    }
+
+    /// `"Offline"`, `"Queued"` or `"Loaded"`.
+    fn get_state(this: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+        let handle = Self::this(this)?;
+        let state = with_world(|world| handle.kind.lookup(world, &handle.name).map(|(_, state)| state))?;
+        Ok(js_string!(state.unwrap_or("Offline")).into())
+    }
+}
+
+impl Class for AssetHandle {
+    const NAME: &'static str = "Asset";
+
+    fn init(class: &mut ClassBuilder<'_>) -> JsResult<()> {
+        let getter = NativeFunction::from_fn_ptr;
+        let mut class = DeclaredClass::new(class, Self::NAME, None);
+        class
+            .accessor("name", getter(Self::get_name), None, "string")
+            .accessor("kind", getter(Self::get_kind), None, "\"mesh\" | \"texture\"")
+            .accessor("id", getter(Self::get_id), None, "number")
+            .accessor(
+                "state",
+                getter(Self::get_state),
+                None,
+                "\"Offline\" | \"Queued\" | \"Loaded\"",
+            );
+        class.finish();
+        Ok(())
+    }
+
+    fn data_constructor(_new_target: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<Self> {
+        Err(JsNativeError::typ()
+            .with_message("use Resources.getMesh or Resources.getTexture to get assets")
+            .into())
+    }
+}
+
+/// Registers global `Resources` object:
+/// - `Resources.meshes()` and `Resources.textures()` list names of known assets,
+/// - `Resources.getMesh(name)` and `Resources.getTexture(name)` return `Asset` or `null`.
+/// Names are file names without extension.
+pub fn register(context: &mut Context) {
+    context.register_global_class::<AssetHandle>().unwrap();
+    GlobalObject::new("Resources")
+        .function("meshes", NativeFunction::from_fn_ptr(meshes), "(): string[]")
+        .function("textures", NativeFunction::from_fn_ptr(textures), "(): string[]")
+        .function(
+            "getMesh",
+            NativeFunction::from_fn_ptr(get_mesh),
+            "(name: string): Asset | null",
+        )
+        .function(
+            "getTexture",
+            NativeFunction::from_fn_ptr(get_texture),
+            "(name: string): Asset | null",
+        )
+        .register(context);
+}
+
+fn list(kind: AssetKind, context: &mut Context) -> JsResult<JsValue> {
+    let names = with_world(|world| kind.names(world))?;
+    let names = names.into_iter().map(|name| js_string!(name).into());
+    Ok(JsArray::from_iter(names, context).into())
+}
+
+fn meshes(_this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    list(AssetKind::Mesh, context)
+}
+
+fn textures(_this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    list(AssetKind::Texture, context)
+}
+
+fn get_mesh(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    AssetHandle::resolve(context, AssetKind::Mesh, &name)
+}
+
+fn get_texture(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    AssetHandle::resolve(context, AssetKind::Texture, &name)
+}
@@ -0,0 +1,29 @@
+use boa_engine::{Context, JsResult, JsValue, NativeFunction};
+
+use super::typings::GlobalObject;
+use super::with_world;
+use crate::core::TimeData;
+
+/// Registers global `Time` object. Values are the ones measured at the beginning of current frame.
+pub fn register(context: &mut Context) {
+    GlobalObject::new("Time")
+        .function("elapsed", NativeFunction::from_fn_ptr(elapsed), "(): number")
+        .function("dt", NativeFunction::from_fn_ptr(dt), "(): number")
+        .function("frameCount", NativeFunction::from_fn_ptr(frame_count), "(): number")
+        .register(context);
+}
+
+fn elapsed(_this: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+    let elapsed = with_world(|world| world.resources.get::<TimeData>().unwrap().elapsed())?;
+    Ok(elapsed.into())
+}
+
+fn dt(_this: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+    let dt = with_world(|world| world.resources.get::<TimeData>().unwrap().dt())?;
+    Ok(dt.into())
+}
+
+fn frame_count(_this: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+    let frame = with_world(|world| world.resources.get::<TimeData>().unwrap().frame_count())?;
+    Ok(frame.into())
+}
@@ -0,0 +1,256 @@
+use boa_engine::object::builtins::JsPromise;
+use boa_engine::{Context, JsArgs, JsData, JsNativeError, JsObject, JsResult, JsValue, NativeFunction};
+use boa_gc::{Finalize, Trace};
+use just_core::ecs::prelude::*;
+
+use super::typings;
+use super::with_world;
+use crate::core::TimeData;
+
+/// When timer fires.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Due {
+    /// Game time in seconds, as measured by `TimeSystem`.
+    Time(f64),
+    Frame(u64),
+}
+
+#[derive(Clone, Trace, Finalize)]
+pub struct Timer {
+    pub id: u32,
+    #[unsafe_ignore_trace]
+    due: Due,
+    /// Period of `setInterval` timers.
+    #[unsafe_ignore_trace]
+    interval: Option<f64>,
+    /// Callback given to `setTimeout`/`setInterval`, or function resolving promise of `wait`/`nextFrame`.
+    pub callback: JsObject,
+    pub args: Vec<JsValue>,
+    /// Game object whose controller created the timer, timer is cancelled when it's destroyed.
+    #[unsafe_ignore_trace]
+    pub owner: Option<Entity>,
+}
+
+/// Pending timers, stored in JS realm.
+#[derive(Default, Trace, Finalize, JsData)]
+struct Timers {
+    last_id: u32,
+    timers: Vec<Timer>,
+    /// Object of controller whose hook or timer is running, new timers belong to it.
+    #[unsafe_ignore_trace]
+    owner: Option<Entity>,
+}
+
+/// Registers global timer functions, delays are in seconds of game time:
+/// - `setTimeout(callback, seconds, ...args)` and `setInterval(callback, seconds, ...args)` return
+///   id to be passed to `clearTimeout`/`clearInterval`,
+/// - `wait(seconds)` returns promise resolved after given time, `nextFrame()` one resolved in next frame.
+/// Callbacks run after controllers are updated. Timers created by controllers are cancelled
+/// together with their game object, pending promises then never resolve.
+pub fn register(context: &mut Context) {
+    context.realm().host_defined_mut().insert(Timers::default());
+
+    let callback = "(callback: (...args: any[]) => void, seconds?: number, ...args: any[]): number";
+    typings::register_function(
+        context,
+        "setTimeout",
+        NativeFunction::from_fn_ptr(set_timeout),
+        callback,
+    );
+    typings::register_function(
+        context,
+        "setInterval",
+        NativeFunction::from_fn_ptr(set_interval),
+        callback,
+    );
+    typings::register_function(
+        context,
+        "clearTimeout",
+        NativeFunction::from_fn_ptr(clear),
+        "(id: number): void",
+    );
+    typings::register_function(
+        context,
+        "clearInterval",
+        NativeFunction::from_fn_ptr(clear),
+        "(id: number): void",
+    );
+    typings::register_function(
+        context,
+        "wait",
+        NativeFunction::from_fn_ptr(wait),
+        "(seconds: number): Promise<void>",
+    );
+    typings::register_function(
+        context,
+        "nextFrame",
+        NativeFunction::from_fn_ptr(next_frame),
+        "(): Promise<void>",
+    );
+}
+
+/// Makes timers created from now on belong to `owner`, returns previous owner.
+pub fn set_owner(context: &mut Context, owner: Option<Entity>) -> Option<Entity> {
+    let realm = context.realm().clone();
+    let mut host_defined = realm.host_defined_mut();
+    std::mem::replace(&mut host_defined.get_mut::<Timers>().unwrap().owner, owner)
+}
+
+/// Object of controller whose hook or timer is running, if any.
+pub fn owner(context: &Context) -> Option<Entity> {
+    context.realm().host_defined().get::<Timers>().unwrap().owner
+}
+
+pub fn cancel_owned(context: &mut Context, owner: Entity) {
+    let realm = context.realm().clone();
+    let mut host_defined = realm.host_defined_mut();
+    let timers = host_defined.get_mut::<Timers>().unwrap();
+    timers.timers.retain(|timer| timer.owner != Some(owner));
+}
+
+pub fn cancel(context: &mut Context, id: u32) {
+    let realm = context.realm().clone();
+    let mut host_defined = realm.host_defined_mut();
+    let timers = host_defined.get_mut::<Timers>().unwrap();
+    timers.timers.retain(|timer| timer.id != id);
+}
+
+/// Distinct owners of pending timers.
+pub fn owners(context: &Context) -> Vec<Entity> {
+    let host_defined = context.realm().host_defined();
+    let mut owners: Vec<Entity> = vec![];
+    for owner in host_defined
+        .get::<Timers>()
+        .unwrap()
+        .timers
+        .iter()
+        .filter_map(|timer| timer.owner)
+    {
+        if !owners.contains(&owner) {
+            owners.push(owner);
+        }
+    }
+    owners
+}
+
+/// Timers due at given time and frame, skipping ones owned by `paused` objects.
+/// Timeouts are removed and intervals rescheduled before they're returned, so callbacks can clear them.
+pub fn take_due(context: &mut Context, now: f64, frame: u64, paused: &[Entity]) -> Vec<Timer> {
+    let realm = context.realm().clone();
+    let mut host_defined = realm.host_defined_mut();
+    let timers = host_defined.get_mut::<Timers>().unwrap();
+    let is_due = |timer: &Timer| {
+        let due = match timer.due {
+            Due::Time(time) => time <= now,
+            Due::Frame(due_frame) => due_frame <= frame,
+        };
+        due && !timer.owner.map(|owner| paused.contains(&owner)).unwrap_or(false)
+    };
+    let due: Vec<Timer> = timers.timers.iter().filter(|timer| is_due(timer)).cloned().collect();
+    timers.timers.retain(|timer| !is_due(timer) || timer.interval.is_some());
+    for timer in timers.timers.iter_mut().filter(|timer| is_due(timer)) {
+        if let (Due::Time(time), Some(interval)) = (timer.due, timer.interval) {
+            // ticks missed in long frames are skipped instead of all firing at once
+            let next = time + interval;
+            timer.due = Due::Time(if next > now { next } else { now + interval });
+        }
+    }
+    due
+}
+
+fn now() -> JsResult<(f64, u64)> {
+    with_world(|world| {
+        let time = world.resources.get::<TimeData>().unwrap();
+        (time.elapsed() as f64, time.frame_count())
+    })
+}
+
+/// Adds timer belonging to current owner (see `set_owner`), returns its id.
+/// Interval timers are rescheduled `interval` seconds after they fire, they have to be due at a time.
+pub fn add(context: &mut Context, due: Due, interval: Option<f64>, callback: JsObject, args: Vec<JsValue>) -> u32 {
+    let realm = context.realm().clone();
+    let mut host_defined = realm.host_defined_mut();
+    let timers = host_defined.get_mut::<Timers>().unwrap();
+    timers.last_id += 1;
+    let id = timers.last_id;
+    let owner = timers.owner;
+    timers.timers.push(Timer {
+        id,
+        due,
+        interval,
+        callback,
+        args,
+        owner,
+    });
+    id
+}
+
+fn seconds_arg(value: &JsValue, context: &mut Context) -> JsResult<f64> {
+    if value.is_undefined() {
+        return Ok(0.0);
+    }
+    let seconds = value.to_number(context)?;
+    if seconds.is_nan() || seconds < 0.0 {
+        return Err(JsNativeError::range()
+            .with_message(format!(
+                "delay must be a non-negative number of seconds, got {}",
+                seconds
+            ))
+            .into());
+    }
+    Ok(seconds)
+}
+
+fn schedule(args: &[JsValue], context: &mut Context, repeat: bool) -> JsResult<JsValue> {
+    let callback = args
+        .get_or_undefined(0)
+        .as_callable()
+        .cloned()
+        .ok_or_else(|| JsNativeError::typ().with_message("callback is not a function"))?;
+    let seconds = seconds_arg(args.get_or_undefined(1), context)?;
+    let (now, _) = now()?;
+    let interval = if repeat { Some(seconds) } else { None };
+    let id = add(
+        context,
+        Due::Time(now + seconds),
+        interval,
+        callback,
+        args.iter().skip(2).cloned().collect(),
+    );
+    Ok(id.into())
+}
+
+fn set_timeout(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    schedule(args, context, false)
+}
+
+/// Interval callbacks run at most once per frame.
+fn set_interval(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    schedule(args, context, true)
+}
+
+/// Clears both timeouts and intervals, unknown ids are ignored.
+fn clear(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let id = args.get_or_undefined(0);
+    if let Some(id) = id.as_number() {
+        cancel(context, id as u32);
+    }
+    Ok(JsValue::undefined())
+}
+
+fn promise(context: &mut Context, due: Due) -> JsValue {
+    let (promise, functions) = JsPromise::new_pending(context);
+    add(context, due, None, functions.resolve.into(), vec![]);
+    promise.into()
+}
+
+fn wait(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let seconds = seconds_arg(args.get_or_undefined(0), context)?;
+    let (now, _) = now()?;
+    Ok(promise(context, Due::Time(now + seconds)))
+}
+
+fn next_frame(_this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let (_, frame) = now()?;
+    Ok(promise(context, Due::Frame(frame + 1)))
+}
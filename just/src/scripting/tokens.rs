@@ -0,0 +1,191 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TokenKind {
+    Word,
+    Literal,
+    Open,
+    Close,
+    Operator,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+    /// Byte offset in script.
+    pub start: usize,
+}
+
+/// Characters joined into a single operator token, `=` alone is assignment.
+const OPERATOR_CHARS: &[u8] = b"=!<>&|+-*%^?~:";
+
+/// After these `/` starts a regex rather than being division.
+const REGEX_KEYWORDS: &[&str] = &[
+    "return",
+    "typeof",
+    "instanceof",
+    "in",
+    "of",
+    "new",
+    "delete",
+    "void",
+    "throw",
+    "case",
+    "do",
+    "else",
+    "yield",
+    "await",
+];
+
+/// Splits script into tokens, skipping comments and whitespace. Strings, template and regex literals
+/// are single tokens. Whether `/` is division or regex is decided by the previous token, like tools
+/// not parsing the whole grammar do, so `/` right after `)` or `}` is always taken as division.
+pub fn tokenize(source: &str) -> Vec<Token<'_>> {
+    let bytes = source.as_bytes();
+    let mut tokens: Vec<Token> = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        let start = i;
+        let kind = if c.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        } else if c == b'/' && matches!(bytes.get(i + 1), Some(b'/') | Some(b'*')) {
+            i = skip_non_code(bytes, i).unwrap();
+            continue;
+        } else if let Some(next) = skip_non_code(bytes, i) {
+            i = next;
+            TokenKind::Literal
+        } else if c == b'/' && regex_allowed(tokens.last()) {
+            i = regex_end(bytes, i);
+            TokenKind::Literal
+        } else if is_identifier_start(c) {
+            i = identifier_end(bytes, i);
+            TokenKind::Word
+        } else if c.is_ascii_digit() {
+            // also takes exponent, fraction and `n` suffix
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'.' || bytes[i] == b'_') {
+                i += 1;
+            }
+            TokenKind::Literal
+        } else if OPERATOR_CHARS.contains(&c) {
+            while i < bytes.len() && OPERATOR_CHARS.contains(&bytes[i]) {
+                i += 1;
+            }
+            TokenKind::Operator
+        } else {
+            i += 1;
+            match c {
+                b'{' | b'(' | b'[' => TokenKind::Open,
+                b'}' | b')' | b']' => TokenKind::Close,
+                _ => TokenKind::Operator,
+            }
+        };
+        tokens.push(Token {
+            kind,
+            text: &source[start..i],
+            start,
+        });
+    }
+    tokens
+}
+
+fn regex_allowed(previous: Option<&Token>) -> bool {
+    let previous = match previous {
+        Some(previous) => previous,
+        None => return true,
+    };
+    match previous.kind {
+        TokenKind::Word => REGEX_KEYWORDS.contains(&previous.text),
+        TokenKind::Literal | TokenKind::Close => false,
+        // `a++ / 2`
+        TokenKind::Operator => !previous.text.ends_with("++") && !previous.text.ends_with("--"),
+        TokenKind::Open => true,
+    }
+}
+
+/// Index right after regex literal starting at `i`, including its flags.
+fn regex_end(bytes: &[u8], mut i: usize) -> usize {
+    i += 1;
+    let mut in_class = false;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'[' => in_class = true,
+            b']' => in_class = false,
+            b'/' if !in_class => break,
+            b'\n' => return i,
+            _ => (),
+        }
+        i += 1;
+    }
+    identifier_end(bytes, (i + 1).min(bytes.len()))
+}
+/// If comment, string or template literal starts at `i`, returns index right after it.
+fn skip_non_code(bytes: &[u8], i: usize) -> Option<usize> {
+    let next = bytes.get(i + 1).copied();
+    match (bytes[i], next) {
+        (b'/', Some(b'/')) => Some(find(bytes, i + 2, b"\n").map(|end| end + 1).unwrap_or(bytes.len())),
+        (b'/', Some(b'*')) => Some(find(bytes, i + 2, b"*/").map(|end| end + 2).unwrap_or(bytes.len())),
+        (quote @ b'\'', _) | (quote @ b'"', _) => {
+            let mut j = i + 1;
+            while j < bytes.len() && bytes[j] != quote {
+                j += if bytes[j] == b'\\' { 2 } else { 1 };
+            }
+            Some((j + 1).min(bytes.len()))
+        }
+        (b'`', _) => {
+            let mut j = i + 1;
+            while j < bytes.len() {
+                match bytes[j] {
+                    b'\\' => j += 2,
+                    b'`' => return Some(j + 1),
+                    b'$' if bytes.get(j + 1) == Some(&b'{') => j = skip_substitution(bytes, j + 2),
+                    _ => j += 1,
+                }
+            }
+            Some(bytes.len())
+        }
+        _ => None,
+    }
+}
+
+/// Skips `${...}` in template literal, `i` is right after `${`.
+fn skip_substitution(bytes: &[u8], mut i: usize) -> usize {
+    let mut depth = 1usize;
+    while i < bytes.len() {
+        if let Some(next) = skip_non_code(bytes, i) {
+            i = next;
+            continue;
+        }
+        match bytes[i] {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => (),
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+fn find(bytes: &[u8], from: usize, pattern: &[u8]) -> Option<usize> {
+    bytes[from.min(bytes.len())..]
+        .windows(pattern.len())
+        .position(|window| window == pattern)
+        .map(|position| from + position)
+}
+
+fn is_identifier_start(c: u8) -> bool {
+    c.is_ascii_alphabetic() || c == b'_' || c == b'$' || c >= 0x80
+}
+
+fn identifier_end(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() && (is_identifier_start(bytes[i]) || bytes[i].is_ascii_digit()) {
+        i += 1;
+    }
+    i
+}
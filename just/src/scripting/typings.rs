@@ -0,0 +1,393 @@
+use boa_engine::class::ClassBuilder;
+use boa_engine::object::ObjectInitializer;
+use boa_engine::property::Attribute;
+use boa_engine::{js_string, Context, JsData, NativeFunction};
+use boa_gc::{Finalize, Trace};
+
+/// TypeScript declarations of script API, collected while bindings are registered,
+/// so `just.d.ts` can't get out of sync with them. Stored in JS realm.
+#[derive(Default)]
+pub struct Declarations {
+    interfaces: Vec<Interface>,
+    aliases: Vec<(String, String)>,
+    functions: Vec<(String, String)>,
+}
+
+impl Finalize for Declarations {}
+
+// SAFETY: only strings, no GC pointers
+unsafe impl Trace for Declarations {
+    boa_gc::empty_trace!();
+}
+
+impl JsData for Declarations {}
+
+/// Where value described by interface is reachable from scripts.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Placement {
+    /// Type only, e.g. object passed to function.
+    None,
+    Global(String),
+    /// Property of other declared interface, e.g. `Math.Vector3`.
+    Property(String),
+}
+
+#[derive(Debug, Clone)]
+struct Property {
+    name: String,
+    get: String,
+    /// `None` for read only properties.
+    set: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Interface {
+    name: String,
+    placement: Placement,
+    properties: Vec<Property>,
+    methods: Vec<(String, String)>,
+    /// Classes get `...Constructor` interface with static methods.
+    class: bool,
+    /// Classes created only by engine have no constructor.
+    constructor: Option<String>,
+    statics: Vec<(String, String)>,
+}
+
+impl Interface {
+    pub fn new(name: &str, placement: Placement) -> Self {
+        Interface {
+            name: name.to_owned(),
+            placement,
+            properties: vec![],
+            methods: vec![],
+            class: false,
+            constructor: None,
+            statics: vec![],
+        }
+    }
+
+    pub fn property(mut self, name: &str, ty: &str, writable: bool) -> Self {
+        self.properties.push(Property {
+            name: name.to_owned(),
+            get: ty.to_owned(),
+            set: if writable { Some(ty.to_owned()) } else { None },
+        });
+        self
+    }
+
+    pub fn optional_property(mut self, name: &str, ty: &str) -> Self {
+        self = self.property(name, ty, true);
+        self.properties.last_mut().unwrap().name.push('?');
+        self
+    }
+
+    pub fn method(mut self, name: &str, signature: &str) -> Self {
+        self.methods.push((name.to_owned(), signature.to_owned()));
+        self
+    }
+
+    /// Method that may be left out, like controller hooks.
+    pub fn optional_method(mut self, name: &str, signature: &str) -> Self {
+        self.methods.push((format!("{}?", name), signature.to_owned()));
+        self
+    }
+
+    fn render(&self, output: &mut String, nested: &[&Interface]) {
+        output.push_str(&format!("interface {} {{\n", self.name));
+        for property in self.properties.iter() {
+            match &property.set {
+                Some(set) if *set != property.get => {
+                    output.push_str(&format!("    get {}(): {};\n", property.name, property.get));
+                    output.push_str(&format!("    set {}(value: {});\n", property.name, set));
+                }
+                Some(_) => output.push_str(&format!("    {}: {};\n", property.name, property.get)),
+                None => output.push_str(&format!("    readonly {}: {};\n", property.name, property.get)),
+            }
+        }
+        for interface in nested {
+            output.push_str(&format!("    {}: {};\n", interface.name, interface.value_type()));
+        }
+        for (name, signature) in self.methods.iter() {
+            output.push_str(&format!("    {}{};\n", name, signature));
+        }
+        output.push_str("}\n");
+
+        if self.class {
+            output.push_str(&format!("interface {}Constructor {{\n", self.name));
+            if let Some(constructor) = &self.constructor {
+                output.push_str(&format!("    new {}: {};\n", constructor, self.name));
+            }
+            output.push_str(&format!("    readonly prototype: {};\n", self.name));
+            for (name, signature) in self.statics.iter() {
+                output.push_str(&format!("    {}{};\n", name, signature));
+            }
+            output.push_str("}\n");
+        }
+        if let Placement::Global(global) = &self.placement {
+            output.push_str(&format!("declare var {}: {};\n", global, self.value_type()));
+        }
+    }
+
+    /// Type of the value scripts see: constructor for classes, the interface itself for objects.
+    fn value_type(&self) -> String {
+        if self.class {
+            format!("{}Constructor", self.name)
+        } else {
+            self.name.clone()
+        }
+    }
+}
+
+/// Adds interface to declarations, merging it with already declared one of the same name.
+pub fn declare(context: &mut Context, interface: Interface) {
+    with_declarations(context, |declarations| {
+        match declarations.interfaces.iter_mut().find(|i| i.name == interface.name) {
+            Some(declared) => {
+                declared.properties.extend(interface.properties);
+                declared.methods.extend(interface.methods);
+                declared.statics.extend(interface.statics);
+                if interface.class {
+                    declared.class = true;
+                    declared.constructor = interface.constructor;
+                }
+                if interface.placement != Placement::None {
+                    declared.placement = interface.placement;
+                }
+            }
+            None => declarations.interfaces.push(interface),
+        }
+    })
+}
+
+/// Declares `type name = definition`.
+pub fn declare_type(context: &mut Context, name: &str, definition: &str) {
+    with_declarations(context, |declarations| {
+        declarations.aliases.push((name.to_owned(), definition.to_owned()))
+    });
+}
+
+/// Registers global function, `signature` is in TypeScript, e.g. `(seconds: number): Promise<void>`.
+pub fn register_function(context: &mut Context, name: &str, function: NativeFunction, signature: &str) {
+    context
+        .register_global_callable(js_string!(name), required_parameters(signature), function)
+        .unwrap();
+    with_declarations(context, |declarations| {
+        declarations.functions.push((name.to_owned(), signature.to_owned()))
+    });
+}
+
+/// Declarations are created on first use, so bindings can be registered in any context.
+fn with_declarations<F: FnOnce(&mut Declarations)>(context: &mut Context, f: F) {
+    let realm = context.realm().clone();
+    let mut host_defined = realm.host_defined_mut();
+    if host_defined.get::<Declarations>().is_none() {
+        host_defined.insert(Declarations::default());
+    }
+    f(host_defined.get_mut::<Declarations>().unwrap());
+}
+
+/// Changes where declared interface is reachable from, e.g. after class was moved.
+pub fn place(context: &mut Context, name: &str, placement: Placement) {
+    declare(context, Interface::new(name, placement));
+}
+
+/// Contents of `just.d.ts` for everything declared in `context`.
+pub fn render(context: &Context) -> String {
+    let mut output = String::from("// Generated by `just typings` from script bindings, do not edit.\n");
+    let host_defined = context.realm().host_defined();
+    let declarations = match host_defined.get::<Declarations>() {
+        Some(declarations) => declarations,
+        None => return output,
+    };
+    for (name, definition) in declarations.aliases.iter() {
+        output.push_str(&format!("\ntype {} = {};\n", name, definition));
+    }
+    for interface in declarations.interfaces.iter() {
+        let nested: Vec<&Interface> = declarations
+            .interfaces
+            .iter()
+            .filter(|nested| nested.placement == Placement::Property(interface.name.clone()))
+            .collect();
+        output.push('\n');
+        interface.render(&mut output, &nested);
+    }
+    if !declarations.functions.is_empty() {
+        output.push('\n');
+    }
+    for (name, signature) in declarations.functions.iter() {
+        output.push_str(&format!("declare function {}{};\n", name, signature));
+    }
+    output
+}
+
+/// Number of required parameters in signature like `(target: Vector3, up?: Vector3): void`,
+/// used as `length` of native functions.
+fn required_parameters(signature: &str) -> usize {
+    let mut depth = 0;
+    let mut parameters: Vec<String> = vec![];
+    let mut previous = ' ';
+    for c in signature.chars() {
+        // `>` of arrow function types doesn't close anything
+        let arrow = previous == '=' && c == '>';
+        previous = c;
+        match c {
+            '(' | '<' | '{' | '[' => depth += 1,
+            ')' | '}' | ']' => depth -= 1,
+            '>' if !arrow => depth -= 1,
+            _ => (),
+        }
+        match c {
+            // parameter list starts after type parameters, if there are any
+            '(' if depth == 1 && parameters.is_empty() => parameters.push(String::new()),
+            ')' if depth == 0 => break,
+            ',' if depth == 1 && !parameters.is_empty() => parameters.push(String::new()),
+            c if !parameters.is_empty() => parameters.last_mut().unwrap().push(c),
+            _ => (),
+        }
+    }
+    parameters
+        .iter()
+        .map(|parameter| parameter.split(':').next().unwrap().trim())
+        .take_while(|name| !name.is_empty() && !name.ends_with('?') && !name.starts_with("..."))
+        .count()
+}
+
+/// Global object with declared members, e.g. `Input`.
+pub struct GlobalObject {
+    interface: Interface,
+    functions: Vec<(String, NativeFunction, usize)>,
+    accessors: Vec<(String, NativeFunction, Option<NativeFunction>)>,
+}
+
+impl GlobalObject {
+    pub fn new(name: &str) -> Self {
+        // `console` is described by `Console` interface
+        let mut type_name = name.to_owned();
+        type_name[..1].make_ascii_uppercase();
+        GlobalObject {
+            interface: Interface::new(&type_name, Placement::Global(name.to_owned())),
+            functions: vec![],
+            accessors: vec![],
+        }
+    }
+
+    /// `signature` is in TypeScript, e.g. `(key: string): boolean`.
+    pub fn function(mut self, name: &str, function: NativeFunction, signature: &str) -> Self {
+        let length = required_parameters(signature);
+        self.functions.push((name.to_owned(), function, length));
+        self.interface.methods.push((name.to_owned(), signature.to_owned()));
+        self
+    }
+
+    pub fn accessor(mut self, name: &str, get: NativeFunction, set: Option<NativeFunction>, ty: &str) -> Self {
+        self.interface = self.interface.property(name, ty, set.is_some());
+        self.accessors.push((name.to_owned(), get, set));
+        self
+    }
+
+    pub fn register(self, context: &mut Context) {
+        let GlobalObject {
+            interface,
+            functions,
+            accessors,
+        } = self;
+        let realm = context.realm().clone();
+        let mut initializer = ObjectInitializer::new(context);
+        for (name, function, length) in functions {
+            initializer.function(function, js_string!(name), length);
+        }
+        for (name, get, set) in accessors {
+            let get = get.to_js_function(&realm);
+            let set = set.map(|set| set.to_js_function(&realm));
+            initializer.accessor(
+                js_string!(name),
+                Some(get),
+                set,
+                Attribute::CONFIGURABLE | Attribute::ENUMERABLE,
+            );
+        }
+        let object = initializer.build();
+        let global = match &interface.placement {
+            Placement::Global(global) => global.clone(),
+            _ => unreachable!(),
+        };
+        context
+            .register_global_property(js_string!(global), object, Attribute::all())
+            .unwrap();
+        declare(context, interface);
+    }
+}
+
+/// Registers class members and declares them at the same time.
+pub struct DeclaredClass<'a, 'b> {
+    class: &'a mut ClassBuilder<'b>,
+    interface: Interface,
+    attribute: Attribute,
+}
+
+impl<'a, 'b> DeclaredClass<'a, 'b> {
+    /// `constructor` is signature of `new`, e.g. `(x?: number, y?: number)`,
+    /// `None` for classes whose objects are only created by engine.
+    pub fn new(class: &'a mut ClassBuilder<'b>, name: &str, constructor: Option<&str>) -> Self {
+        let mut interface = Interface::new(name, Placement::Global(name.to_owned()));
+        interface.class = true;
+        interface.constructor = constructor.map(str::to_owned);
+        DeclaredClass {
+            class,
+            interface,
+            attribute: Attribute::CONFIGURABLE,
+        }
+    }
+
+    /// Makes accessors added afterwards enumerable, e.g. vector components.
+    pub fn enumerable(&mut self) -> &mut Self {
+        self.attribute |= Attribute::ENUMERABLE;
+        self
+    }
+
+    pub fn accessor(&mut self, name: &str, get: NativeFunction, set: Option<NativeFunction>, ty: &str) -> &mut Self {
+        let set_ty = set.as_ref().map(|_| ty);
+        self.accessor_types(name, get, set, ty, set_ty)
+    }
+
+    /// Accessor whose setter accepts other types than getter returns.
+    pub fn accessor_types(
+        &mut self,
+        name: &str,
+        get: NativeFunction,
+        set: Option<NativeFunction>,
+        get_ty: &str,
+        set_ty: Option<&str>,
+    ) -> &mut Self {
+        let realm = self.class.context().realm().clone();
+        let get = get.to_js_function(&realm);
+        let set = set.map(|set| set.to_js_function(&realm));
+        self.class.accessor(js_string!(name), Some(get), set, self.attribute);
+        self.interface.properties.push(Property {
+            name: name.to_owned(),
+            get: get_ty.to_owned(),
+            set: set_ty.map(str::to_owned),
+        });
+        self
+    }
+
+    pub fn method(&mut self, name: &str, function: NativeFunction, signature: &str) -> &mut Self {
+        self.class
+            .method(js_string!(name), required_parameters(signature), function);
+        self.interface.methods.push((name.to_owned(), signature.to_owned()));
+        self
+    }
+
+    pub fn static_method(&mut self, name: &str, function: NativeFunction, signature: &str) -> &mut Self {
+        self.class
+            .static_method(js_string!(name), required_parameters(signature), function);
+        self.interface.statics.push((name.to_owned(), signature.to_owned()));
+        self
+    }
+
+    /// Stores declaration, must be called after all members are added.
+    pub fn finish(self) {
+        let DeclaredClass { class, interface, .. } = self;
+        declare(class.context(), interface);
+    }
+}
@@ -0,0 +1,280 @@
+use boa_engine::object::builtins::JsArray;
+use boa_engine::{js_string, Context, JsArgs, JsNativeError, JsObject, JsResult, JsValue, NativeFunction};
+use just_assets::AssetStorage;
+use just_core::commands::Commands;
+use just_core::ecs::prelude::*;
+use just_core::game_object::GameObject;
+use just_core::hierarchy::{HierarchyError, TransformHierarchy};
+use just_core::math::{Quat, Vec3};
+use just_wgpu::{Mesh, Texture};
+
+use super::camera;
+use super::controller::Controllers;
+use super::game_object::{self, GameObjectHandle};
+use super::resources::{AssetHandle, AssetKind};
+use super::typings::{self, GlobalObject, Interface, Placement};
+use super::{math, with_world};
+
+/// Registers global `World` object:
+/// - `World.createGameObject(parent?)` creates empty object,
+/// - `World.spawn({name, parent, position, rotation, scale, mesh, texture, controller})` creates object
+///   from descriptor, every field is optional but `mesh` and `texture` go together, they are either `Asset`s or names,
+/// - `World.findByName(name)` returns array of objects with given name,
+/// - `World.destroy(go)` removes object with its children at the end of frame,
+/// - `World.setParent(go, parent)` reparents object, `null` parent makes it a root,
+/// - `World.setCameraPosition(position)` and `World.setCameraRotation(rotation)`, same as setting
+///   `Camera.position` and `Camera.rotation`.
+pub fn register(context: &mut Context) {
+    typings::declare(
+        context,
+        Interface::new("SpawnDescriptor", Placement::None)
+            .optional_property("name", "string")
+            .optional_property("parent", "GameObject | null")
+            .optional_property("position", "Vector3")
+            .optional_property("rotation", "Quat")
+            .optional_property("scale", "Vector3")
+            .optional_property("mesh", "Asset | string")
+            .optional_property("texture", "Asset | string")
+            .optional_property("controller", "object"),
+    );
+    GlobalObject::new("World")
+        .function(
+            "createGameObject",
+            NativeFunction::from_fn_ptr(create_game_object),
+            "(parent?: GameObject | null): GameObject",
+        )
+        .function(
+            "spawn",
+            NativeFunction::from_fn_ptr(spawn),
+            "(descriptor: SpawnDescriptor): GameObject",
+        )
+        .function(
+            "findByName",
+            NativeFunction::from_fn_ptr(find_by_name),
+            "(name: string): GameObject[]",
+        )
+        .function(
+            "findByTag",
+            NativeFunction::from_fn_ptr(find_by_tag),
+            "(tag: string): GameObject[]",
+        )
+        .function(
+            "findInLayer",
+            NativeFunction::from_fn_ptr(find_in_layer),
+            "(layer: number): GameObject[]",
+        )
+        .function(
+            "destroy",
+            NativeFunction::from_fn_ptr(destroy),
+            "(go: GameObject): void",
+        )
+        .function(
+            "setParent",
+            NativeFunction::from_fn_ptr(set_parent),
+            "(go: GameObject, parent: GameObject | null): void",
+        )
+        .function(
+            "setCameraPosition",
+            NativeFunction::from_fn_ptr(camera::set_position),
+            "(position: Vector3): void",
+        )
+        .function(
+            "setCameraRotation",
+            NativeFunction::from_fn_ptr(camera::set_rotation),
+            "(rotation: Quat): void",
+        )
+        .register(context);
+}
+
+/// Object description accepted by `World.spawn`.
+struct Descriptor {
+    name: Option<String>,
+    parent: Option<JsValue>,
+    position: Option<Vec3>,
+    rotation: Option<Quat>,
+    scale: Option<Vec3>,
+    renderable: Option<(String, String)>,
+    controller: Option<JsObject>,
+}
+
+impl Descriptor {
+    fn from_js(value: &JsValue, context: &mut Context) -> JsResult<Self> {
+        let object = value
+            .as_object()
+            .ok_or_else(|| JsNativeError::typ().with_message("World.spawn expects descriptor object"))?;
+        let mut field = |name: &str| -> JsResult<Option<JsValue>> {
+            let value = object.get(js_string!(name), context)?;
+            Ok(if value.is_undefined() { None } else { Some(value) })
+        };
+        let name = field("name")?;
+        let parent = field("parent")?;
+        let position = field("position")?;
+        let rotation = field("rotation")?;
+        let scale = field("scale")?;
+        let mesh = field("mesh")?;
+        let texture = field("texture")?;
+        let controller = field("controller")?;
+
+        let mut string = |value: Option<JsValue>| -> JsResult<Option<String>> {
+            value
+                .map(|value| Ok(value.to_string(context)?.to_std_string_escaped()))
+                .transpose()
+        };
+        let name = string(name)?;
+        let mesh = mesh
+            .map(|mesh| AssetHandle::name_arg(&mesh, AssetKind::Mesh, context))
+            .transpose()?;
+        let texture = texture
+            .map(|texture| AssetHandle::name_arg(&texture, AssetKind::Texture, context))
+            .transpose()?;
+        let renderable = match (mesh, texture) {
+            (Some(mesh), Some(texture)) => Some((mesh, texture)),
+            (None, None) => None,
+            _ => {
+                return Err(JsNativeError::typ()
+                    .with_message("mesh and texture must be given together")
+                    .into())
+            }
+        };
+        let controller = match controller {
+            Some(controller) => Some(
+                controller
+                    .as_object()
+                    .cloned()
+                    .ok_or_else(|| JsNativeError::typ().with_message("controller must be an object"))?,
+            ),
+            None => None,
+        };
+        Ok(Descriptor {
+            name,
+            parent,
+            position: position.map(|v| math::from_js::<Vec3>(&v, context)).transpose()?,
+            rotation: rotation.map(|v| math::from_js::<Quat>(&v, context)).transpose()?,
+            scale: scale.map(|v| math::from_js::<Vec3>(&v, context)).transpose()?,
+            renderable,
+            controller,
+        })
+    }
+}
+
+/// Checks that mesh and texture are loaded, so that renderable creation can't fail later.
+fn check_renderable(world: &World, mesh: &str, texture: &str) -> JsResult<()> {
+    let meshes = world.resources.get::<AssetStorage<Mesh>>().unwrap();
+    let textures = world.resources.get::<AssetStorage<Texture>>().unwrap();
+    if meshes.get_handle(mesh).and_then(|h| meshes.get_value(&h)).is_none() {
+        return Err(JsNativeError::error()
+            .with_message(format!("mesh '{}' is not loaded", mesh))
+            .into());
+    }
+    if textures
+        .get_handle(texture)
+        .and_then(|h| textures.get_value(&h))
+        .is_none()
+    {
+        return Err(JsNativeError::error()
+            .with_message(format!("texture '{}' is not loaded", texture))
+            .into());
+    }
+    Ok(())
+}
+
+fn create_game_object(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let parent = args.get_or_undefined(0);
+    // checked before creating, so that failure doesn't leave orphan object behind
+    if !parent.is_null_or_undefined() {
+        GameObjectHandle::alive_id(parent)?;
+    }
+    let id = with_world(GameObject::create_empty)?;
+    game_object::set_parent(id, parent)?;
+    Ok(GameObjectHandle::new_object(context, id).into())
+}
+
+fn spawn(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let Descriptor {
+        name,
+        parent,
+        position,
+        rotation,
+        scale,
+        renderable,
+        controller,
+    } = Descriptor::from_js(args.get_or_undefined(0), context)?;
+    if let Some(parent) = &parent {
+        if !parent.is_null() {
+            GameObjectHandle::alive_id(parent)?;
+        }
+    }
+    if let Some((mesh, texture)) = &renderable {
+        with_world(|world| check_renderable(world, mesh, texture))??;
+    }
+
+    let id = with_world(|world| -> Result<Entity, HierarchyError> {
+        let id = GameObject::create_empty(world);
+        if let Some(name) = name {
+            GameObject::set_name(world, id, name)?;
+        }
+        if let Some(position) = position {
+            TransformHierarchy::set_local_position(world, id, position)?;
+        }
+        if let Some(rotation) = rotation {
+            TransformHierarchy::set_local_rotation(world, id, rotation)?;
+        }
+        if let Some(scale) = scale {
+            TransformHierarchy::set_local_scale(world, id, scale)?;
+        }
+        // created when commands are applied after scripts
+        if let Some((mesh, texture)) = renderable {
+            let mut commands = world.resources.get_mut::<Commands>().unwrap();
+            commands.add_renderable(id, &mesh, &texture);
+        }
+        Ok(id)
+    })?
+    .map_err(game_object::hierarchy_error)?;
+    if let Some(parent) = &parent {
+        game_object::set_parent(id, parent)?;
+    }
+    if let Some(controller) = controller {
+        let component = Controllers::register(context, id, controller)?;
+        with_world(|world| world.add_component(id, component))?;
+    }
+    Ok(GameObjectHandle::new_object(context, id).into())
+}
+
+fn handles(context: &mut Context, ids: Vec<Entity>) -> JsValue {
+    let handles = ids
+        .into_iter()
+        .map(|id| GameObjectHandle::new_object(context, id).into())
+        .collect::<Vec<JsValue>>();
+    JsArray::from_iter(handles, context).into()
+}
+
+fn find_by_name(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let found = with_world(|world| GameObject::find_by_name(world, &name))?;
+    Ok(handles(context, found))
+}
+
+fn find_by_tag(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let tag = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let found = with_world(|world| GameObject::find_by_tag(world, &tag))?;
+    Ok(handles(context, found))
+}
+
+/// Layers past 31 are empty.
+fn find_in_layer(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let layer = args.get_or_undefined(0).to_u32(context)?;
+    let found = with_world(|world| GameObject::find_in_layer(world, layer))?;
+    Ok(handles(context, found))
+}
+
+fn destroy(_this: &JsValue, args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+    let id = GameObjectHandle::alive_id(args.get_or_undefined(0))?;
+    with_world(|world| GameObject::delete(world, id))?;
+    Ok(JsValue::undefined())
+}
+
+fn set_parent(_this: &JsValue, args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+    let id = GameObjectHandle::alive_id(args.get_or_undefined(0))?;
+    game_object::set_parent(id, args.get_or_undefined(1))?;
+    Ok(JsValue::undefined())
+}
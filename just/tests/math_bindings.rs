@@ -0,0 +1,143 @@
+//! Checks that `Math` script bindings give the same results as glam.
+
+use boa_engine::{Context, Source};
+use just::scripting::math::{self, MathValue};
+use just_core::glam::EulerRot;
+use just_core::math::{Matrix, Quat, Vec2, Vec3, Vec4};
+
+fn context() -> Context {
+    let mut context = Context::default();
+    math::register(&mut context).unwrap();
+    context
+}
+
+fn eval<T: MathValue>(context: &mut Context, code: &str) -> T {
+    let value = context.eval(Source::from_bytes(code)).unwrap();
+    math::from_js::<T>(&value, context).unwrap()
+}
+
+fn eval_number(context: &mut Context, code: &str) -> f32 {
+    let value = context.eval(Source::from_bytes(code)).unwrap();
+    value.to_number(context).unwrap() as f32
+}
+
+#[test]
+fn vector3_matches_glam() {
+    let mut context = context();
+    context
+        .eval(Source::from_bytes(
+            "var a = new Math.Vector3(1, 2.5, -3); var b = new Math.Vector3(-4, 0.5, 8);",
+        ))
+        .unwrap();
+    let a = Vec3::new(1.0, 2.5, -3.0);
+    let b = Vec3::new(-4.0, 0.5, 8.0);
+
+    assert_eq!(eval::<Vec3>(&mut context, "a.add(b)"), a + b);
+    assert_eq!(eval::<Vec3>(&mut context, "a.sub(b)"), a - b);
+    assert_eq!(eval::<Vec3>(&mut context, "a.mul(b)"), a * b);
+    assert_eq!(eval::<Vec3>(&mut context, "a.mul(1.5)"), a * 1.5);
+    assert_eq!(eval::<Vec3>(&mut context, "a.div(b)"), a / b);
+    assert_eq!(eval::<Vec3>(&mut context, "a.negate()"), -a);
+    assert_eq!(eval::<Vec3>(&mut context, "a.cross(b)"), a.cross(b));
+    assert_eq!(eval::<Vec3>(&mut context, "a.normalize()"), a.normalize());
+    assert_eq!(eval::<Vec3>(&mut context, "a.lerp(b, 0.25)"), a.lerp(b, 0.25));
+    assert_eq!(eval_number(&mut context, "a.dot(b)"), a.dot(b));
+    assert_eq!(eval_number(&mut context, "a.length()"), a.length());
+    assert_eq!(eval_number(&mut context, "a.distance(b)"), a.distance(b));
+}
+
+#[test]
+fn vector2_and_vector4_match_glam() {
+    let mut context = context();
+    let a = Vec2::new(3.0, -0.5);
+    let b = Vec2::new(1.0, 2.0);
+    assert_eq!(
+        eval::<Vec2>(&mut context, "new Math.Vector2(3, -0.5).add(new Math.Vector2(1, 2))"),
+        a + b
+    );
+    assert_eq!(
+        eval_number(&mut context, "new Math.Vector2(3, -0.5).dot(new Math.Vector2(1, 2))"),
+        a.dot(b)
+    );
+
+    let c = Vec4::new(1.0, 2.0, 3.0, 4.0);
+    let d = Vec4::new(0.5, -1.0, 0.0, 2.0);
+    assert_eq!(
+        eval::<Vec4>(
+            &mut context,
+            "new Math.Vector4(1, 2, 3, 4).lerp(new Math.Vector4(0.5, -1, 0, 2), 0.5)"
+        ),
+        c.lerp(d, 0.5)
+    );
+    let normalized = eval::<Vec4>(&mut context, "new Math.Vector4(1, 2, 3, 4).normalize()");
+    assert!(normalized.abs_diff_eq(c.normalize(), 1e-6), "{:?}", normalized);
+}
+
+#[test]
+fn quat_matches_glam() {
+    let mut context = context();
+    context
+        .eval(Source::from_bytes(
+            "var q = Math.quaternionFromEulerAngles(new Math.Vector3(-0.55, 0.2, 0)); \
+             var r = Math.Quat.fromAxisAngle(new Math.Vector3(0, 1, 0), 1.25);",
+        ))
+        .unwrap();
+    let q = Quat::from_euler(EulerRot::XYZ, -0.55f64 as f32, 0.2f64 as f32, 0.0);
+    let r = Quat::from_axis_angle(Vec3::Y, 1.25);
+    let v = Vec3::new(1.0, 2.0, 3.0);
+
+    assert_eq!(eval::<Quat>(&mut context, "q"), q);
+    assert_eq!(eval::<Quat>(&mut context, "r"), r);
+    assert_eq!(eval::<Quat>(&mut context, "q.mul(r)"), q * r);
+    assert_eq!(eval::<Vec3>(&mut context, "q.mul(new Math.Vector3(1, 2, 3))"), q * v);
+    assert_eq!(eval::<Quat>(&mut context, "q.inverse()"), q.inverse());
+    assert_eq!(eval::<Quat>(&mut context, "q.slerp(r, 0.5)"), q.slerp(r, 0.5));
+    let (x, y, z) = q.to_euler(EulerRot::XYZ);
+    assert_eq!(eval::<Vec3>(&mut context, "q.toEuler()"), Vec3::new(x, y, z));
+}
+
+#[test]
+fn matrix_matches_glam() {
+    let mut context = context();
+    context
+        .eval(Source::from_bytes(
+            "var m = Math.Matrix.fromScaleRotationTranslation(new Math.Vector3(2, 2, 2), \
+             Math.Quat.fromAxisAngle(new Math.Vector3(0, 0, 1), 0.5), new Math.Vector3(1, -2, 3));",
+        ))
+        .unwrap();
+    let m = Matrix::from_scale_rotation_translation(
+        Vec3::splat(2.0),
+        Quat::from_axis_angle(Vec3::Z, 0.5),
+        Vec3::new(1.0, -2.0, 3.0),
+    );
+    let p = Vec3::new(0.5, 1.0, -1.0);
+
+    assert_eq!(eval::<Matrix>(&mut context, "m"), m);
+    assert_eq!(eval::<Matrix>(&mut context, "m.mul(m.inverse())"), m * m.inverse());
+    assert_eq!(eval::<Matrix>(&mut context, "m.transpose()"), m.transpose());
+    assert_eq!(
+        eval::<Vec3>(&mut context, "m.transformPoint(new Math.Vector3(0.5, 1, -1))"),
+        m.transform_point3(p)
+    );
+    assert_eq!(
+        eval::<Vec3>(&mut context, "m.transformVector(new Math.Vector3(0.5, 1, -1))"),
+        m.transform_vector3(p)
+    );
+    assert_eq!(eval_number(&mut context, "m.get(3, 1)"), m.w_axis.y);
+}
+
+#[test]
+fn values_are_copied() {
+    let mut context = context();
+    let original = eval::<Vec3>(
+        &mut context,
+        "var a = new Math.Vector3(1, 2, 3); var b = a.clone(); b.x = 10; a.add(b); a",
+    );
+    assert_eq!(original, Vec3::new(1.0, 2.0, 3.0));
+
+    // plain objects with matching components are accepted as arguments
+    assert_eq!(
+        eval::<Vec3>(&mut context, "new Math.Vector3(1, 1, 1).add({x: 1, y: 2, z: 3})"),
+        Vec3::new(2.0, 3.0, 4.0)
+    );
+}
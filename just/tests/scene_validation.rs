@@ -0,0 +1,130 @@
+//! Checks that problems in scene files are reported with their path and position.
+
+use just::scene::{locate, parse_file, PathSegment, Scene, ValuePath};
+
+const JSON: &str = r#"{
+    "name": "level",
+    "camera_rotation": [0.0, 0.0, 0.0],
+    "viewport_height": 10.0,
+    "objects": [
+        { "name": "a" },
+        { "name": "b", "children": [{ "name": "c" }] },
+        { "name": "d", "position": [1.0, 2.0, 3.0] },
+        {
+            "name": "e",
+            "renderable": {
+                "texture": "tex1",
+                "mesh": 5
+            }
+        }
+    ]
+}
+"#;
+
+const RON: &str = r#"Scene(
+    name: "level",
+    camera_rotation: (0.0, 0.0, 0.0),
+    viewport_height: 10.0,
+    objects: [
+        (name: "a"),
+        (name: "b", children: Some([(name: "c")])),
+        /* comment with "objects" and [brackets] in it */
+        (name: "d", position: Some((1.0, 2.0, 3.0))),
+        (
+            name: "e",
+            renderable: Some(Renderable(
+                texture: "tex1",
+                mesh: 5,
+            )),
+        ),
+    ],
+)
+"#;
+
+fn mesh_path() -> ValuePath {
+    ValuePath(vec![
+        PathSegment::Field("objects".to_owned()),
+        PathSegment::Index(3),
+        PathSegment::Field("renderable".to_owned()),
+        PathSegment::Field("mesh".to_owned()),
+    ])
+}
+
+#[test]
+fn path_is_displayed_like_in_code() {
+    assert_eq!(mesh_path().to_string(), "objects[3].renderable.mesh");
+    assert_eq!(ValuePath::default().to_string(), "");
+}
+
+#[test]
+fn locates_values_in_json() {
+    assert_eq!(locate(JSON, &mesh_path()), Some((13, 25)));
+    assert_eq!(locate(JSON, &ValuePath::default()), Some((1, 1)));
+    let child_name = ValuePath(vec![
+        PathSegment::Field("objects".to_owned()),
+        PathSegment::Index(1),
+        PathSegment::Field("children".to_owned()),
+        PathSegment::Index(0),
+        PathSegment::Field("name".to_owned()),
+    ]);
+    assert_eq!(locate(JSON, &child_name), Some((7, 47)));
+}
+
+#[test]
+fn locates_values_in_ron() {
+    assert_eq!(locate(RON, &mesh_path()), Some((14, 23)));
+    let child_name = ValuePath(vec![
+        PathSegment::Field("objects".to_owned()),
+        PathSegment::Index(1),
+        PathSegment::Field("children".to_owned()),
+        PathSegment::Index(0),
+        PathSegment::Field("name".to_owned()),
+    ]);
+    assert_eq!(locate(RON, &child_name), Some((7, 44)));
+}
+
+#[test]
+fn missing_values_are_not_located() {
+    let mut path = mesh_path();
+    path.0[1] = PathSegment::Index(4);
+    assert_eq!(locate(JSON, &path), None);
+    assert_eq!(locate(RON, &path), None);
+    let unknown_field = ValuePath(vec![PathSegment::Field("camera".to_owned())]);
+    assert_eq!(locate(JSON, &unknown_field), None);
+    assert_eq!(locate(RON, &unknown_field), None);
+}
+
+#[test]
+fn invalid_json_value_is_reported_with_path_and_position() {
+    let error = parse_file::<Scene>("level.scene", JSON).unwrap_err();
+    assert_eq!(error.path, mesh_path());
+    assert_eq!(error.position, Some((13, 25)));
+    assert_eq!(
+        error.to_string(),
+        "level.scene:13:25: objects[3].renderable.mesh: invalid type: integer `5`, expected a string"
+    );
+}
+
+#[test]
+fn invalid_ron_value_is_reported_with_path_and_position() {
+    let error = parse_file::<Scene>("level.scene", RON).unwrap_err();
+    assert_eq!(error.path, mesh_path());
+    assert_eq!(error.position, Some((14, 23)));
+}
+
+#[test]
+fn missing_field_is_located_by_path() {
+    let text = RON
+        .replace("                texture: \"tex1\",\n", "")
+        .replace("mesh: 5", "mesh: \"cube\"");
+    let error = parse_file::<Scene>("level.scene", &text).unwrap_err();
+    assert_eq!(
+        error.path,
+        ValuePath(vec![
+            PathSegment::Field("objects".to_owned()),
+            PathSegment::Index(3),
+            PathSegment::Field("renderable".to_owned()),
+        ])
+    );
+    assert_eq!(error.position, Some((12, 25)));
+}
@@ -0,0 +1,115 @@
+//! Checks that hooks breaking `ScriptLimits` are stopped and their controllers disabled.
+
+use just::core::TimeSystem;
+use just::scene::ScriptName;
+use just::scripting::{ScriptLimits, ScriptSystem};
+use just_core::ecs::prelude::*;
+use just_core::game_object::GameObject;
+use just_input::InputSystem;
+use just_wgpu::Ui;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+fn scripts_dir(name: &str, script: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("just_script_limits_{}_{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("test.js"), script).unwrap();
+    dir
+}
+
+fn world() -> World {
+    let mut world = World::default();
+    InputSystem::initialize(&mut world);
+    GameObject::initialize(&mut world);
+    TimeSystem::initialize(&mut world);
+    Ui::initialize(&mut world);
+    // generous time, so it's the step budget that stops hooks
+    let limits = ScriptLimits {
+        hook_time: Duration::from_secs(60),
+        hook_steps: 100_000,
+        ..ScriptLimits::default()
+    };
+    world.resources.insert(limits);
+    world
+}
+
+/// Runs `update()` of controller declared by `script` and returns reason of disabling it.
+fn disabled_reason(name: &str, script: &str) -> Option<String> {
+    let dir = scripts_dir(name, script);
+    let mut world = world();
+    let mut scripts = ScriptSystem::initialize(&mut world, dir.to_str().unwrap());
+    let id = GameObject::create_empty(&mut world);
+    world.add_component(id, ScriptName("Test".to_owned()));
+
+    let started = Instant::now();
+    scripts.update(&mut world);
+    assert!(started.elapsed() < Duration::from_secs(10), "hook wasn't stopped");
+    std::fs::remove_dir_all(dir).unwrap();
+
+    let disabled = scripts.disabled_controllers();
+    assert!(disabled.iter().all(|(_, entity, _)| *entity == id));
+    disabled.into_iter().next().map(|(_, _, reason)| reason)
+}
+
+#[test]
+fn nested_loops_are_stopped() {
+    let script = r#"
+        class Test {
+            update(dt) {
+                let sum = 0;
+                for (let i = 0; i < 1000000; i++) {
+                    for (let j = 0; j < 1000000; j++) {
+                        sum += j;
+                    }
+                }
+            }
+        }
+    "#;
+    let reason = disabled_reason("nested", script).expect("controller wasn't disabled");
+    assert!(reason.contains("too many steps"), "{}", reason);
+}
+
+#[test]
+fn loops_in_called_functions_count_for_hook() {
+    let script = r#"
+        function count(n) {
+            let sum = 0;
+            while (sum < n) { sum++; }
+            return sum;
+        }
+        class Test {
+            update(dt) {
+                "use strict";
+                let total = 0;
+                for (const n of [1, 2, 3]) { total += count(50000); }
+            }
+        }
+    "#;
+    assert!(disabled_reason("calls", script).is_some());
+}
+
+#[test]
+fn hooks_within_limits_keep_running() {
+    let script = r#"
+        class Test {
+            update(dt) {
+                let sum = 0;
+                for (let i = 0; i < 1000; i++) { sum += i; }
+                const twice = [1, 2, 3].map((x) => { return x * 2; });
+            }
+        }
+    "#;
+    assert_eq!(disabled_reason("within", script), None);
+}
+
+#[test]
+fn scripts_cant_compile_code_from_strings() {
+    let script = r#"
+        class Test {
+            update(dt) {
+                new Function("while (true) {}")();
+            }
+        }
+    "#;
+    assert_eq!(disabled_reason("eval", script), None);
+}
@@ -0,0 +1,91 @@
+//! Checks that classes are found in scripts for hot reload and error locations.
+
+use just::scripting::reload::{class_declarations, line_column, method_offset};
+
+fn names(source: &str) -> Vec<String> {
+    class_declarations(source).into_iter().map(|class| class.name).collect()
+}
+
+#[test]
+fn finds_class_declarations() {
+    let source = "class A {\n    update(dt) {}\n}\n\nclass B extends A {}\n";
+    let classes = class_declarations(source);
+    assert_eq!(classes.len(), 2);
+    assert_eq!(classes[0].name, "A");
+    assert_eq!(classes[0].text, "class A {\n    update(dt) {}\n}");
+    assert_eq!(classes[0].start, 0);
+    assert_eq!(classes[1].name, "B");
+    assert_eq!(classes[1].text, "class B extends A {}");
+    assert_eq!(line_column(source, classes[1].start), (5, 1));
+}
+
+#[test]
+fn finds_assigned_class_expressions() {
+    let source = "test_script = class {\n    start() {}\n}\nconst Other = class Named extends test_script {};\n";
+    let classes = class_declarations(source);
+    assert_eq!(classes.len(), 2);
+    assert_eq!(classes[0].name, "test_script");
+    assert_eq!(classes[0].text, "class {\n    start() {}\n}");
+    assert_eq!(line_column(source, classes[0].start), (1, 15));
+    assert_eq!(classes[1].name, "Other");
+    assert_eq!(classes[1].text, "class Named extends test_script {}");
+}
+
+#[test]
+fn ignores_classes_that_are_not_top_level() {
+    let source = r#"
+        function make() {
+            class Inner {}
+            return class {};
+        }
+        {
+            class InBlock {}
+        }
+        register(class Argument {});
+        let notClass = 5;
+        a.b = class {};
+        class Top {}
+    "#;
+    assert_eq!(names(source), vec!["Top"]);
+}
+
+#[test]
+fn braces_in_regex_strings_and_comments_are_skipped() {
+    let source = r#"
+        const open = /\{[}]/g;
+        const half = width / 2 / scale;
+        let text = "class Fake {" + '}' + `${ { a: "}" }.a }`;
+        // class Commented {
+        /* } class AlsoCommented { */
+        class Real {
+            check(s) {
+                return /}/.test(s) && s.split(/[{]/).length > 1;
+            }
+            update() {}
+        }
+    "#;
+    let classes = class_declarations(source);
+    assert_eq!(classes.len(), 1);
+    assert_eq!(classes[0].name, "Real");
+    assert!(classes[0].text.starts_with("class Real {"));
+    assert!(classes[0].text.ends_with("update() {}\n        }"));
+}
+
+#[test]
+fn script_with_syntax_error_has_no_classes() {
+    assert!(class_declarations("class A {\n    update( {}\n}\n").is_empty());
+}
+
+#[test]
+fn finds_methods_at_class_body_level() {
+    let class = "class A extends mix(B, { update() {} }) {\n    speed = update();\n    start() { this.update(); }\n    update(dt) {}\n}";
+    let offset = method_offset(class, "update").unwrap();
+    assert_eq!(line_column(class, offset), (4, 5));
+    assert_eq!(line_column(class, method_offset(class, "start").unwrap()), (3, 5));
+    assert_eq!(method_offset(class, "missing"), None);
+}
+
+#[test]
+fn method_offset_stops_at_class_end() {
+    assert_eq!(method_offset("class A {}\nclass B { update() {} }", "update"), None);
+}
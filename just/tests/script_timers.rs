@@ -0,0 +1,93 @@
+//! Checks which script timers are due and how intervals are rescheduled.
+
+use boa_engine::{Context, JsObject, Source};
+use just::scripting::timers::{self, Due, Timer};
+use just_core::ecs::prelude::*;
+
+fn context() -> Context {
+    let mut context = Context::default();
+    timers::register(&mut context);
+    context
+}
+
+fn callback(context: &mut Context) -> JsObject {
+    let callback = context.eval(Source::from_bytes("() => {}")).unwrap();
+    callback.as_object().cloned().unwrap()
+}
+
+fn add(context: &mut Context, due: Due, interval: Option<f64>) -> u32 {
+    let callback = callback(context);
+    timers::add(context, due, interval, callback, vec![])
+}
+
+fn ids(timers: Vec<Timer>) -> Vec<u32> {
+    timers.into_iter().map(|timer| timer.id).collect()
+}
+
+fn entities(world: &mut World, count: usize) -> Vec<Entity> {
+    world.insert((), vec![(0u32,); count]).to_vec()
+}
+
+#[test]
+fn intervals_are_rescheduled_and_timeouts_removed() {
+    let mut context = context();
+    let interval = add(&mut context, Due::Time(1.0), Some(1.0));
+    let timeout = add(&mut context, Due::Time(1.5), None);
+
+    assert!(timers::take_due(&mut context, 0.5, 1, &[]).is_empty());
+    assert_eq!(ids(timers::take_due(&mut context, 1.0, 2, &[])), vec![interval]);
+    assert_eq!(ids(timers::take_due(&mut context, 1.5, 3, &[])), vec![timeout]);
+    assert!(timers::take_due(&mut context, 1.9, 4, &[]).is_empty());
+    assert_eq!(ids(timers::take_due(&mut context, 2.0, 5, &[])), vec![interval]);
+    // ticks missed in a long frame fire once, next one is a full interval later
+    assert_eq!(ids(timers::take_due(&mut context, 5.5, 6, &[])), vec![interval]);
+    assert!(timers::take_due(&mut context, 6.0, 7, &[]).is_empty());
+    assert_eq!(ids(timers::take_due(&mut context, 6.5, 8, &[])), vec![interval]);
+}
+
+#[test]
+fn timers_of_paused_owners_wait() {
+    let mut world = World::default();
+    let owners = entities(&mut world, 2);
+    let mut context = context();
+    timers::set_owner(&mut context, Some(owners[0]));
+    let paused = add(&mut context, Due::Time(0.0), None);
+    timers::set_owner(&mut context, Some(owners[1]));
+    let running = add(&mut context, Due::Time(0.0), None);
+    timers::set_owner(&mut context, None);
+    let unowned = add(&mut context, Due::Time(0.0), None);
+
+    let due = timers::take_due(&mut context, 1.0, 1, &owners[..1]);
+    assert_eq!(due[0].owner, Some(owners[1]));
+    assert_eq!(due[1].owner, None);
+    assert_eq!(ids(due), vec![running, unowned]);
+    assert_eq!(ids(timers::take_due(&mut context, 2.0, 2, &[])), vec![paused]);
+    assert!(timers::take_due(&mut context, 3.0, 3, &[]).is_empty());
+}
+
+#[test]
+fn next_frame_is_due_by_frame_not_time() {
+    let mut context = context();
+    let next_frame = add(&mut context, Due::Frame(3), None);
+
+    assert!(timers::take_due(&mut context, 100.0, 2, &[]).is_empty());
+    assert_eq!(ids(timers::take_due(&mut context, 0.0, 3, &[])), vec![next_frame]);
+    assert!(timers::take_due(&mut context, 0.0, 4, &[]).is_empty());
+}
+
+#[test]
+fn owned_timers_are_cancelled() {
+    let mut world = World::default();
+    let owner = entities(&mut world, 1)[0];
+    let mut context = context();
+    timers::set_owner(&mut context, Some(owner));
+    add(&mut context, Due::Time(0.0), Some(1.0));
+    add(&mut context, Due::Frame(1), None);
+    timers::set_owner(&mut context, None);
+    let unowned = add(&mut context, Due::Time(0.0), None);
+    assert_eq!(timers::owners(&context), vec![owner]);
+
+    timers::cancel_owned(&mut context, owner);
+    assert!(timers::owners(&context).is_empty());
+    assert_eq!(ids(timers::take_due(&mut context, 1.0, 1, &[])), vec![unowned]);
+}
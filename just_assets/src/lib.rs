@@ -85,6 +85,12 @@ impl<T> Clone for Handle<T> {
     }
 }
 
+impl<T> Handle<T> {
+    pub fn id(&self) -> usize {
+        self.id
+    }
+}
+
 impl<T> std::fmt::Debug for Handle<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.id.fmt(f)
@@ -170,32 +176,76 @@ impl<T> AssetStorage<T> {
             println!("processing {} file: {:?}", ext, name);
             let name = name.file_stem().unwrap().to_str().unwrap();
             let result = p(data, name);
-            if self.names.contains_key(name) {
-                let id = self.names[name];
-                self.assets.get_mut(&id.id).unwrap().state = AssetState::Loaded(result.0);
-            } else {
-                let id = self.last_id + 1;
-                self.last_id += 1;
-                self.names.insert(
-                    name.to_owned(),
-                    Handle {
-                        id,
-                        _phantom: Default::default(),
-                    },
-                );
-                self.assets.insert(
-                    id,
-                    Asset {
-                        state: AssetState::Loaded(result.0),
-                    },
-                );
-            }
+            self.set_loaded(name, result.0);
             println!("all assets: {:#?}", self.names);
             // println!("all assets data: {:#?}", self.assets);
             result.1
         });
     }
 
+    /// Same as `process`, but files that failed to load are reported and left in `Offline` state.
+    pub fn try_process<E: std::fmt::Display, F: FnMut(&[u8], &str) -> Result<T, E>>(
+        &mut self,
+        manager: &mut AssetManager,
+        ext: &str,
+        mut p: F,
+    ) {
+        manager.process_extension(ext, |path, data| {
+            println!("processing {} file: {:?}", ext, path);
+            let name = path.file_stem().unwrap().to_str().unwrap();
+            match p(data, name) {
+                Ok(value) => {
+                    self.set_loaded(name, value);
+                    true
+                }
+                Err(error) => {
+                    println!("couldn't load {}: {}", path.display(), error);
+                    false
+                }
+            }
+        });
+    }
+
+    /// Replaces value of an asset, registering it if name is not known yet.
+    pub fn set_loaded(&mut self, name: &str, value: T) -> Handle<T> {
+        if self.names.contains_key(name) {
+            let id = self.names[name];
+            self.assets.get_mut(&id.id).unwrap().state = AssetState::Loaded(value);
+            id
+        } else {
+            let id = self.last_id + 1;
+            self.last_id += 1;
+            let handle = Handle {
+                id,
+                _phantom: Default::default(),
+            };
+            self.names.insert(name.to_owned(), handle);
+            self.assets.insert(
+                id,
+                Asset {
+                    state: AssetState::Loaded(value),
+                },
+            );
+            handle
+        }
+    }
+
+    /// Drops value of an asset, keeping its name registered.
+    pub fn set_offline(&mut self, name: &str) {
+        if let Some(handle) = self.names.get(name) {
+            self.assets.get_mut(&handle.id).unwrap().state = AssetState::Offline;
+        }
+    }
+
+    /// Names of all known assets, loaded or not.
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.names.keys().map(|name| name.as_str())
+    }
+
+    pub fn get_state(&self, handle: &Handle<T>) -> Option<&AssetState<T>> {
+        self.assets.get(&handle.id).map(|asset| &asset.state)
+    }
+
     pub fn get_handle(&self, name: &str) -> Option<Handle<T>> {
         self.names.get(name).copied()
     }
@@ -0,0 +1,77 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use just_core::ecs::prelude::*;
+use just_core::game_object::GameObject;
+use just_core::hierarchy::TransformHierarchy;
+use just_core::math::Vec3;
+use just_core::transform::TransformSystem;
+
+const OBJECTS: usize = 100_000;
+
+/// `OBJECTS` game objects, object `i` is child of `parent(i)` (which is lower than `i`), propagated once.
+fn hierarchy(parent: impl Fn(usize) -> Option<usize>) -> (World, Vec<Entity>) {
+    let mut world = World::default();
+    GameObject::initialize(&mut world);
+    let mut objects = Vec::with_capacity(OBJECTS);
+    for _ in 0..OBJECTS {
+        let id = GameObject::create_empty(&mut world);
+        TransformHierarchy::set_local_position(&mut world, id, Vec3::new(1.0, 0.0, 0.0)).unwrap();
+        objects.push(id);
+    }
+    // linked bottom up, so cycle checks of `set_parent` don't walk whole chain
+    for i in (0..OBJECTS).rev() {
+        if let Some(parent) = parent(i) {
+            TransformHierarchy::set_parent(&mut world, objects[i], Some(objects[parent])).unwrap();
+        }
+    }
+    TransformSystem::update(&mut world);
+    (world, objects)
+}
+
+fn flat(c: &mut Criterion) {
+    let (mut world, objects) = hierarchy(|_| None);
+    c.bench_function("flat 100k, all moved", |b| {
+        b.iter(|| {
+            for id in objects.iter().copied() {
+                TransformHierarchy::set_local_position(&mut world, id, Vec3::new(2.0, 0.0, 0.0)).unwrap();
+            }
+            TransformSystem::update(&mut world);
+        })
+    });
+}
+
+fn tree(c: &mut Criterion) {
+    let (mut world, objects) = hierarchy(|i| if i == 0 { None } else { Some((i - 1) / 10) });
+    let root = objects[0];
+    c.bench_function("tree 100k, root moved", |b| {
+        b.iter(|| {
+            TransformHierarchy::set_local_position(&mut world, root, Vec3::new(2.0, 0.0, 0.0)).unwrap();
+            TransformSystem::update(&mut world);
+        })
+    });
+    c.bench_function("tree 100k, 1000 leaves moved", |b| {
+        b.iter(|| {
+            for id in objects.iter().rev().step_by(10).take(1000).copied() {
+                TransformHierarchy::set_local_position(&mut world, id, Vec3::new(2.0, 0.0, 0.0)).unwrap();
+            }
+            TransformSystem::update(&mut world);
+        })
+    });
+    c.bench_function("tree 100k, nothing moved", |b| {
+        b.iter(|| TransformSystem::update(&mut world))
+    });
+}
+
+fn chain(c: &mut Criterion) {
+    let (mut world, objects) = hierarchy(|i| if i == 0 { None } else { Some(i - 1) });
+    c.bench_function("chain 100k, every 100th moved", |b| {
+        b.iter(|| {
+            for id in objects.iter().step_by(100).copied() {
+                TransformHierarchy::set_local_position(&mut world, id, Vec3::new(2.0, 0.0, 0.0)).unwrap();
+            }
+            TransformSystem::update(&mut world);
+        })
+    });
+}
+
+criterion_group!(benches, flat, tree, chain);
+criterion_main!(benches);
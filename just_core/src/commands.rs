@@ -0,0 +1,333 @@
+use crate::ecs::prelude::*;
+use crate::game_object::GameObject;
+use crate::hierarchy::{HierarchyError, TransformHierarchy};
+use std::collections::HashMap;
+
+/// Handle of object created through `Commands`, it becomes an entity when commands are applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Placeholder(u32);
+
+/// Object command refers to, existing one or one created by earlier command.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Target {
+    Entity(Entity),
+    Placeholder(Placeholder),
+}
+
+impl From<Entity> for Target {
+    fn from(id: Entity) -> Self {
+        Target::Entity(id)
+    }
+}
+
+impl From<Placeholder> for Target {
+    fn from(placeholder: Placeholder) -> Self {
+        Target::Placeholder(placeholder)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CommandError {
+    Hierarchy(HierarchyError),
+    /// Placeholder whose object wasn't created, because command creating it failed.
+    Unresolved(Placeholder),
+    /// Renderable was recorded, but no `RenderableCreator` is registered.
+    NoRenderableCreator,
+}
+
+impl From<HierarchyError> for CommandError {
+    fn from(error: HierarchyError) -> Self {
+        CommandError::Hierarchy(error)
+    }
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandError::Hierarchy(error) => write!(f, "{}", error),
+            CommandError::Unresolved(placeholder) => write!(f, "object {:?} wasn't created", placeholder),
+            CommandError::NoRenderableCreator => write!(f, "renderables can't be created"),
+        }
+    }
+}
+
+/// Copies component of first object to the second one, used when objects are cloned.
+pub type ComponentCloner = fn(&mut World, Entity, Entity);
+
+/// Gives object renderable with given mesh and texture names, registered by rendering system.
+pub type RenderableCreator = fn(&mut World, Entity, &str, &str);
+
+type ComponentCommand = Box<dyn FnOnce(&mut World, Entity) + Send + Sync>;
+
+enum Command {
+    Create(Placeholder),
+    Clone(Target, Placeholder),
+    Delete(Target),
+    SetParent(Target, Option<Target>),
+    Rename(Target, String),
+    /// Adding or removing component.
+    Component(Target, ComponentCommand),
+    /// Mesh and texture names, kept readable until the renderable is created.
+    Renderable(Target, String, String),
+}
+
+/// Structural changes of the world recorded to be applied later, stored in world resources.
+/// Systems can record them while iterating queries, `Engine` applies them between systems.
+/// Commands are applied in order they were recorded, failed ones are skipped.
+#[derive(Default)]
+pub struct Commands {
+    last_placeholder: u32,
+    commands: Vec<Command>,
+    /// Entities created for placeholders, kept while they're alive.
+    resolved: HashMap<Placeholder, Entity>,
+    cloners: Vec<ComponentCloner>,
+    renderable_creator: Option<RenderableCreator>,
+}
+
+impl Commands {
+    /// Empty game object, see `GameObject::create_empty`.
+    pub fn create(&mut self) -> Placeholder {
+        let placeholder = self.next_placeholder();
+        self.commands.push(Command::Create(placeholder));
+        placeholder
+    }
+
+    /// Copy of the object and its descendants, with the same parent. Besides name, tags, layers,
+    /// active state and transform, only components with registered cloners are copied.
+    pub fn clone_object<T: Into<Target>>(&mut self, source: T) -> Placeholder {
+        let placeholder = self.next_placeholder();
+        self.commands.push(Command::Clone(source.into(), placeholder));
+        placeholder
+    }
+
+    /// Marks object for deletion, like `GameObject::delete`.
+    pub fn delete<T: Into<Target>>(&mut self, target: T) {
+        self.commands.push(Command::Delete(target.into()));
+    }
+
+    pub fn set_parent<T: Into<Target>>(&mut self, target: T, parent: Option<Target>) {
+        self.commands.push(Command::SetParent(target.into(), parent));
+    }
+
+    pub fn rename<T: Into<Target>>(&mut self, target: T, name: &str) {
+        self.commands.push(Command::Rename(target.into(), name.to_owned()));
+    }
+
+    pub fn add_component<C: Send + Sync + 'static, T: Into<Target>>(&mut self, target: T, component: C) {
+        let command = Box::new(move |world: &mut World, id: Entity| {
+            world.add_component(id, component);
+        });
+        self.commands.push(Command::Component(target.into(), command));
+    }
+
+    /// Component type goes first, e.g. `commands.remove_component::<Renderable, _>(id)`.
+    pub fn remove_component<C: Send + Sync + 'static, T: Into<Target>>(&mut self, target: T) {
+        let command = Box::new(|world: &mut World, id: Entity| {
+            world.remove_component::<C>(id);
+        });
+        self.commands.push(Command::Component(target.into(), command));
+    }
+
+    /// Renderable is created when commands are applied, so assets have to be loaded by then.
+    pub fn add_renderable<T: Into<Target>>(&mut self, target: T, mesh: &str, texture: &str) {
+        self.commands
+            .push(Command::Renderable(target.into(), mesh.to_owned(), texture.to_owned()));
+    }
+
+    /// Mesh and texture names of renderable recorded for the object and not created yet.
+    pub fn queued_renderable(&self, id: Entity) -> Option<(&str, &str)> {
+        self.commands.iter().rev().find_map(|command| match command {
+            Command::Renderable(target, mesh, texture) if Self::target_is(&self.resolved, *target, id) => {
+                Some((mesh.as_str(), texture.as_str()))
+            }
+            _ => None,
+        })
+    }
+
+    /// Same as `queued_renderable`, names can be changed before the renderable is created.
+    pub fn queued_renderable_mut(&mut self, id: Entity) -> Option<(&mut String, &mut String)> {
+        let resolved = &self.resolved;
+        self.commands.iter_mut().rev().find_map(|command| match command {
+            Command::Renderable(target, mesh, texture) if Self::target_is(resolved, *target, id) => {
+                Some((mesh, texture))
+            }
+            _ => None,
+        })
+    }
+
+    /// Makes `clone_object` copy component `C`.
+    pub fn register_clone<C: Clone + Send + Sync + 'static>(&mut self) {
+        self.cloners.push(clone_component::<C>);
+    }
+
+    /// Makes `clone_object` call `cloner`, for components that can't be just cloned.
+    pub fn register_cloner(&mut self, cloner: ComponentCloner) {
+        self.cloners.push(cloner);
+    }
+
+    /// Makes `add_renderable` work, without it recorded renderables fail to apply.
+    pub fn register_renderable_creator(&mut self, creator: RenderableCreator) {
+        self.renderable_creator = Some(creator);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Entity created for `placeholder` once commands are applied, forgotten by the first `apply` after the object
+    /// is removed.
+    pub fn resolve(&self, placeholder: Placeholder) -> Option<Entity> {
+        self.resolved.get(&placeholder).copied()
+    }
+
+    fn target_is(resolved: &HashMap<Placeholder, Entity>, target: Target, id: Entity) -> bool {
+        match target {
+            Target::Entity(entity) => entity == id,
+            Target::Placeholder(placeholder) => resolved.get(&placeholder) == Some(&id),
+        }
+    }
+
+    fn next_placeholder(&mut self) -> Placeholder {
+        self.last_placeholder += 1;
+        Placeholder(self.last_placeholder)
+    }
+
+    /// Applies recorded commands, including ones recorded while they're applied. Returns errors of failed ones.
+    /// Placeholders of removed objects are forgotten here, commands recorded for them fail as unresolved.
+    pub fn apply(world: &mut World) -> Vec<CommandError> {
+        {
+            let mut buffer = world.resources.get_mut::<Commands>().unwrap();
+            buffer.resolved.retain(|_, id| world.is_alive(*id));
+        }
+        let mut errors = vec![];
+        loop {
+            let (commands, cloners, renderable_creator) = {
+                let mut buffer = world.resources.get_mut::<Commands>().unwrap();
+                (
+                    std::mem::take(&mut buffer.commands),
+                    buffer.cloners.clone(),
+                    buffer.renderable_creator,
+                )
+            };
+            if commands.is_empty() {
+                break;
+            }
+            for command in commands {
+                if let Err(error) = Self::apply_one(world, command, &cloners, renderable_creator) {
+                    errors.push(error);
+                }
+            }
+        }
+        errors
+    }
+
+    fn apply_one(
+        world: &mut World,
+        command: Command,
+        cloners: &[ComponentCloner],
+        renderable_creator: Option<RenderableCreator>,
+    ) -> Result<(), CommandError> {
+        match command {
+            Command::Create(placeholder) => {
+                let id = GameObject::create_empty(world);
+                Self::set_resolved(world, placeholder, id);
+            }
+            Command::Clone(source, placeholder) => {
+                let source = Self::entity(world, source)?;
+                let parent = TransformHierarchy::get_parent(world, source)?;
+                let id = clone_object(world, source, parent, cloners)?;
+                Self::set_resolved(world, placeholder, id);
+            }
+            Command::Delete(target) => {
+                let id = Self::entity(world, target)?;
+                if !world.is_alive(id) {
+                    return Err(HierarchyError::DeadEntity(id).into());
+                }
+                GameObject::delete(world, id);
+            }
+            Command::SetParent(target, parent) => {
+                let id = Self::entity(world, target)?;
+                let parent = parent.map(|parent| Self::entity(world, parent)).transpose()?;
+                TransformHierarchy::set_parent(world, id, parent)?;
+            }
+            Command::Rename(target, name) => {
+                let id = Self::entity(world, target)?;
+                GameObject::set_name(world, id, name)?;
+            }
+            Command::Component(target, command) => {
+                let id = Self::entity(world, target)?;
+                if !world.is_alive(id) {
+                    return Err(HierarchyError::DeadEntity(id).into());
+                }
+                command(world, id);
+            }
+            Command::Renderable(target, mesh, texture) => {
+                let id = Self::entity(world, target)?;
+                if !world.is_alive(id) {
+                    return Err(HierarchyError::DeadEntity(id).into());
+                }
+                let creator = renderable_creator.ok_or(CommandError::NoRenderableCreator)?;
+                creator(world, id, &mesh, &texture);
+            }
+        }
+        Ok(())
+    }
+
+    fn entity(world: &World, target: Target) -> Result<Entity, CommandError> {
+        match target {
+            Target::Entity(id) => Ok(id),
+            Target::Placeholder(placeholder) => world
+                .resources
+                .get::<Commands>()
+                .unwrap()
+                .resolve(placeholder)
+                .ok_or(CommandError::Unresolved(placeholder)),
+        }
+    }
+
+    fn set_resolved(world: &World, placeholder: Placeholder, id: Entity) {
+        let mut buffer = world.resources.get_mut::<Commands>().unwrap();
+        buffer.resolved.insert(placeholder, id);
+    }
+}
+
+fn clone_component<C: Clone + Send + Sync + 'static>(world: &mut World, source: Entity, target: Entity) {
+    let component = world.get_component::<C>(source).map(|component| (*component).clone());
+    if let Some(component) = component {
+        world.add_component(target, component);
+    }
+}
+
+fn clone_object(
+    world: &mut World,
+    source: Entity,
+    parent: Option<Entity>,
+    cloners: &[ComponentCloner],
+) -> Result<Entity, HierarchyError> {
+    let object = world
+        .get_component::<GameObject>(source)
+        .map(|object| (*object).clone())
+        .ok_or_else(|| HierarchyError::missing::<GameObject>(world, source))?;
+    let position = TransformHierarchy::get_local_position(world, source)?;
+    let rotation = TransformHierarchy::get_local_rotation(world, source)?;
+    let scale = TransformHierarchy::get_local_scale(world, source)?;
+
+    let id = GameObject::create_empty(world);
+    GameObject::set_name(world, id, object.name.clone())?;
+    for tag in object.tags.iter() {
+        GameObject::add_tag(world, id, tag)?;
+    }
+    GameObject::set_layer_mask(world, id, object.layer_mask)?;
+    GameObject::set_active(world, id, object.active_self)?;
+    TransformHierarchy::set_local_position(world, id, position)?;
+    TransformHierarchy::set_local_rotation(world, id, rotation)?;
+    TransformHierarchy::set_local_scale(world, id, scale)?;
+    TransformHierarchy::set_parent(world, id, parent)?;
+    for cloner in cloners {
+        cloner(world, source, id);
+    }
+    for child in object.children {
+        clone_object(world, child, Some(id), cloners)?;
+    }
+    Ok(id)
+}
@@ -0,0 +1,155 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use shrev::{EventChannel, ReaderId};
+use std::collections::HashMap;
+
+/// JSON-like value carried by events, shared by Rust systems and scripts.
+pub type EventPayload = serde_json::Value;
+
+/// Rust struct sent through `EventBus`, declared with `EventBus::declare_type`.
+/// Payloads published under its name (also by scripts) have to deserialize into it.
+pub trait Event: Serialize + DeserializeOwned + 'static {
+    const NAME: &'static str;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventError {
+    Undeclared(String),
+    /// Payload doesn't match Rust type declared for the event.
+    InvalidPayload {
+        name: String,
+        message: String,
+    },
+}
+
+impl std::fmt::Display for EventError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EventError::Undeclared(name) => write!(f, "event '{}' is not declared", name),
+            EventError::InvalidPayload { name, message } => write!(f, "invalid payload of '{}': {}", name, message),
+        }
+    }
+}
+
+/// Subscription to one named event, every subscriber reads all events on its own.
+pub struct EventReader {
+    name: String,
+    reader: ReaderId<EventPayload>,
+}
+
+impl EventReader {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Checks payload against Rust type declared for the event.
+type PayloadValidator = fn(&EventPayload) -> Result<(), String>;
+
+struct NamedChannel {
+    channel: EventChannel<EventPayload>,
+    /// `None` for events declared only by name.
+    validate: Option<PayloadValidator>,
+}
+
+/// Named event channels stored in world resources.
+/// Events have to be declared before they're published or subscribed to, either by name
+/// or with Rust type describing their payload.
+#[derive(Default)]
+pub struct EventBus {
+    channels: HashMap<String, NamedChannel>,
+}
+
+impl EventBus {
+    /// Declares event with any payload, does nothing if it's already declared.
+    pub fn declare(&mut self, name: &str) {
+        if !self.channels.contains_key(name) {
+            let channel = NamedChannel {
+                channel: EventChannel::new(),
+                validate: None,
+            };
+            self.channels.insert(name.to_owned(), channel);
+        }
+    }
+
+    /// Declares event whose payload is `T`, also when it was declared by name before.
+    pub fn declare_type<T: Event>(&mut self) {
+        self.declare(T::NAME);
+        self.channels.get_mut(T::NAME).unwrap().validate = Some(validate::<T>);
+    }
+
+    pub fn is_declared(&self, name: &str) -> bool {
+        self.channels.contains_key(name)
+    }
+
+    /// Names of declared events, sorted.
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.channels.keys().map(String::as_str).collect();
+        names.sort();
+        names
+    }
+
+    pub fn publish(&mut self, name: &str, payload: EventPayload) -> Result<(), EventError> {
+        let named = self
+            .channels
+            .get_mut(name)
+            .ok_or_else(|| EventError::Undeclared(name.to_owned()))?;
+        if let Some(validate) = named.validate {
+            validate(&payload).map_err(|message| EventError::InvalidPayload {
+                name: name.to_owned(),
+                message,
+            })?;
+        }
+        named.channel.single_write(payload);
+        Ok(())
+    }
+
+    /// Publishes Rust event, declaring its type if needed.
+    pub fn send<T: Event>(&mut self, event: &T) -> Result<(), EventError> {
+        if !self.is_declared(T::NAME) {
+            self.declare_type::<T>();
+        }
+        let payload = serde_json::to_value(event).map_err(|error| EventError::InvalidPayload {
+            name: T::NAME.to_owned(),
+            message: error.to_string(),
+        })?;
+        self.publish(T::NAME, payload)
+    }
+
+    /// Reader gets events published after it was registered.
+    pub fn register_reader(&mut self, name: &str) -> Result<EventReader, EventError> {
+        let named = self
+            .channels
+            .get_mut(name)
+            .ok_or_else(|| EventError::Undeclared(name.to_owned()))?;
+        Ok(EventReader {
+            name: name.to_owned(),
+            reader: named.channel.register_reader(),
+        })
+    }
+
+    /// Payloads published since last read by `reader`.
+    pub fn read<'a>(&'a self, reader: &mut EventReader) -> impl Iterator<Item = &'a EventPayload> {
+        self.channels[&reader.name].channel.read(&mut reader.reader)
+    }
+
+    /// Events of type `T` published since last read by `reader`, which has to be registered for `T::NAME`.
+    /// Payloads published before `T` was declared may fail to deserialize.
+    pub fn read_typed<T: Event>(&self, reader: &mut EventReader) -> Vec<Result<T, EventError>> {
+        debug_assert_eq!(reader.name, T::NAME);
+        self.read(reader)
+            .map(|payload| {
+                serde_json::from_value(payload.clone()).map_err(|error| EventError::InvalidPayload {
+                    name: T::NAME.to_owned(),
+                    message: error.to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
+fn validate<T: Event>(payload: &EventPayload) -> Result<(), String> {
+    serde_json::from_value::<T>(payload.clone())
+        .map(|_| ())
+        .map_err(|error| error.to_string())
+}
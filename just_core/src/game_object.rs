@@ -1,8 +1,22 @@
+use crate::commands::Commands;
 use crate::ecs::prelude::*;
+use crate::hierarchy::{HierarchyError, RootObjects};
+use crate::object_index::GameObjectIndex;
+use crate::transform::DirtyTransforms;
+
+/// Layer mask of new objects, they're only in layer 0.
+pub const DEFAULT_LAYER_MASK: u32 = 1;
 
 #[derive(Clone)]
 pub struct GameObject {
-    pub name: String,
+    /// Name, tags and layers are indexed by `GameObjectIndex`, so they're changed only through `GameObject` methods.
+    pub(crate) name: String,
+    pub(crate) tags: Vec<String>,
+    pub(crate) layer_mask: u32,
+    /// State set by `GameObject::set_active`.
+    pub(crate) active_self: bool,
+    /// Whether object and all its ancestors are active, kept up to date on `set_active` and reparenting.
+    pub(crate) active_in_hierarchy: bool,
     pub children: Vec<Entity>,
     pub parent: Option<Entity>,
 }
@@ -20,29 +34,181 @@ impl GameObject {
     pub fn new() -> Self {
         GameObject {
             name: "".to_string(),
+            tags: vec![],
+            layer_mask: DEFAULT_LAYER_MASK,
+            active_self: true,
+            active_in_hierarchy: true,
             children: vec![],
             parent: Option::None,
         }
     }
     pub fn initialize(world: &mut World) {
         world.resources.insert(ObjectsToDelete::new());
+        world.resources.insert(crate::hierarchy::HierarchyChannel::new());
+        world.resources.insert(GameObjectIndex::default());
+        world.resources.insert(Commands::default());
+        world.resources.insert(DirtyTransforms::default());
+        world.resources.insert(RootObjects::default());
+    }
+
+    /// Same as `GameObject::is_active_in_hierarchy`, for systems iterating over components.
+    /// Inactive objects are skipped by rendering and scripts, systems that update objects should skip them too.
+    pub fn active_in_hierarchy(&self) -> bool {
+        self.active_in_hierarchy
     }
 }
 
 impl GameObject {
-    pub fn get_name(world: &World, id: Entity) -> String {
-        world.get_component::<GameObject>(id).unwrap().name.clone()
+    pub fn get_name(world: &World, id: Entity) -> Result<String, HierarchyError> {
+        world
+            .get_component::<GameObject>(id)
+            .map(|object| object.name.clone())
+            .ok_or_else(|| HierarchyError::missing::<GameObject>(world, id))
     }
-    pub fn set_name(world: &mut World, id: Entity, new_name: String) {
-        world.get_component_mut::<GameObject>(id).unwrap().name = new_name;
+    pub fn set_name(world: &mut World, id: Entity, new_name: String) -> Result<(), HierarchyError> {
+        let old_name = world
+            .get_component_mut::<GameObject>(id)
+            .map(|mut object| std::mem::replace(&mut object.name, new_name.clone()))
+            .ok_or_else(|| HierarchyError::missing::<GameObject>(world, id))?;
+        let mut index = world.resources.get_mut::<GameObjectIndex>().unwrap();
+        index.rename(id, &old_name, &new_name);
+        Ok(())
     }
 
+    /// Objects with given name, looked up in `GameObjectIndex`. Unnamed objects can't be found.
     pub fn find_by_name(world: &World, name: &str) -> Vec<Entity> {
-        Read::<GameObject>::query()
-            .iter_entities_immutable(world)
-            .filter(|(x, y)| y.name == name)
-            .map(|(x, _y)| x)
-            .collect()
+        let index = world.resources.get::<GameObjectIndex>().unwrap();
+        index.by_name(name).to_vec()
+    }
+
+    /// Deactivating the object deactivates its whole subtree, activating it restores states of descendants
+    /// set with their own `set_active` calls.
+    pub fn set_active(world: &mut World, id: Entity, active: bool) -> Result<(), HierarchyError> {
+        world
+            .get_component_mut::<GameObject>(id)
+            .map(|mut object| object.active_self = active)
+            .ok_or_else(|| HierarchyError::missing::<GameObject>(world, id))?;
+        Self::refresh_active(world, id)
+    }
+
+    /// Own state of the object, regardless of its ancestors.
+    pub fn is_active_self(world: &World, id: Entity) -> Result<bool, HierarchyError> {
+        world
+            .get_component::<GameObject>(id)
+            .map(|object| object.active_self)
+            .ok_or_else(|| HierarchyError::missing::<GameObject>(world, id))
+    }
+
+    /// Whether the object and all its ancestors are active.
+    pub fn is_active_in_hierarchy(world: &World, id: Entity) -> Result<bool, HierarchyError> {
+        world
+            .get_component::<GameObject>(id)
+            .map(|object| object.active_in_hierarchy)
+            .ok_or_else(|| HierarchyError::missing::<GameObject>(world, id))
+    }
+
+    /// Recomputes `active_in_hierarchy` of the object and its descendants after its state or parent changed.
+    pub(crate) fn refresh_active(world: &mut World, id: Entity) -> Result<(), HierarchyError> {
+        let parent_active = match crate::hierarchy::TransformHierarchy::get_parent(world, id)? {
+            Some(parent) => Self::is_active_in_hierarchy(world, parent)?,
+            None => true,
+        };
+        let mut stack = vec![(id, parent_active)];
+        while let Some((current, parent_active)) = stack.pop() {
+            let refreshed = world.get_component_mut::<GameObject>(current).map(|mut object| {
+                let active = object.active_self && parent_active;
+                let changed = object.active_in_hierarchy != active;
+                object.active_in_hierarchy = active;
+                (active, changed, object.children.clone())
+            });
+            let (active, changed, children) = match refreshed {
+                Some(refreshed) => refreshed,
+                None => continue,
+            };
+            // descendants of unchanged object already match it
+            if changed {
+                stack.extend(children.into_iter().map(|child| (child, active)));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_tags(world: &World, id: Entity) -> Result<Vec<String>, HierarchyError> {
+        world
+            .get_component::<GameObject>(id)
+            .map(|object| object.tags.clone())
+            .ok_or_else(|| HierarchyError::missing::<GameObject>(world, id))
+    }
+
+    pub fn has_tag(world: &World, id: Entity, tag: &str) -> Result<bool, HierarchyError> {
+        Ok(Self::get_tags(world, id)?.iter().any(|own| own == tag))
+    }
+
+    /// Adds tag unless object already has it.
+    pub fn add_tag(world: &mut World, id: Entity, tag: &str) -> Result<(), HierarchyError> {
+        let added = world
+            .get_component_mut::<GameObject>(id)
+            .map(|mut object| {
+                let added = !object.tags.iter().any(|own| own == tag);
+                if added {
+                    object.tags.push(tag.to_owned());
+                }
+                added
+            })
+            .ok_or_else(|| HierarchyError::missing::<GameObject>(world, id))?;
+        if added {
+            world.resources.get_mut::<GameObjectIndex>().unwrap().add_tag(id, tag);
+        }
+        Ok(())
+    }
+
+    /// Returns whether object had the tag.
+    pub fn remove_tag(world: &mut World, id: Entity, tag: &str) -> Result<bool, HierarchyError> {
+        let removed = world
+            .get_component_mut::<GameObject>(id)
+            .map(|mut object| {
+                let count = object.tags.len();
+                object.tags.retain(|own| own != tag);
+                object.tags.len() != count
+            })
+            .ok_or_else(|| HierarchyError::missing::<GameObject>(world, id))?;
+        if removed {
+            world
+                .resources
+                .get_mut::<GameObjectIndex>()
+                .unwrap()
+                .remove_tag(id, tag);
+        }
+        Ok(removed)
+    }
+
+    pub fn find_by_tag(world: &World, tag: &str) -> Vec<Entity> {
+        let index = world.resources.get::<GameObjectIndex>().unwrap();
+        index.by_tag(tag).to_vec()
+    }
+
+    /// Bit `n` of the mask is set when object is in layer `n`.
+    pub fn get_layer_mask(world: &World, id: Entity) -> Result<u32, HierarchyError> {
+        world
+            .get_component::<GameObject>(id)
+            .map(|object| object.layer_mask)
+            .ok_or_else(|| HierarchyError::missing::<GameObject>(world, id))
+    }
+
+    pub fn set_layer_mask(world: &mut World, id: Entity, mask: u32) -> Result<(), HierarchyError> {
+        let old_mask = world
+            .get_component_mut::<GameObject>(id)
+            .map(|mut object| std::mem::replace(&mut object.layer_mask, mask))
+            .ok_or_else(|| HierarchyError::missing::<GameObject>(world, id))?;
+        let mut index = world.resources.get_mut::<GameObjectIndex>().unwrap();
+        index.set_layers(id, old_mask, mask);
+        Ok(())
+    }
+
+    /// Objects in layer `layer` (0 to 31).
+    pub fn find_in_layer(world: &World, layer: u32) -> Vec<Entity> {
+        let index = world.resources.get::<GameObjectIndex>().unwrap();
+        index.in_layer(layer).to_vec()
     }
 
     pub fn delete(world: &mut World, id: Entity) {
@@ -50,37 +216,94 @@ impl GameObject {
     }
 
     pub fn remove_marked(world: &mut World) {
-        let mut to_destroy = world.resources.get_mut::<ObjectsToDelete>().unwrap();
-        let objects = std::mem::replace(&mut to_destroy.0, vec![]);
-        drop(to_destroy);
-        for obj in objects.into_iter() {
-            // might have been removed as child of other object
-            if !world.is_alive(obj) {
-                continue;
+        Self::remove_marked_with(world, |_, _| ());
+    }
+
+    /// Same as `remove_marked`, but calls `before_removal` for every removed object
+    /// (children before their parents) while it is still alive.
+    /// Objects marked from inside of `before_removal` are removed in the same call.
+    pub fn remove_marked_with<F: FnMut(&mut World, Entity)>(world: &mut World, mut before_removal: F) {
+        loop {
+            let mut to_destroy = world.resources.get_mut::<ObjectsToDelete>().unwrap();
+            let objects = std::mem::replace(&mut to_destroy.0, vec![]);
+            drop(to_destroy);
+            if objects.is_empty() {
+                break;
+            }
+            for obj in objects.into_iter() {
+                // might have been removed as child of other object
+                if !world.is_alive(obj) {
+                    continue;
+                }
+                if let Err(error) = Self::remove_game_object_with(world, obj, &mut before_removal) {
+                    println!("Couldn't remove object: {}", error);
+                }
             }
-            Self::remove_game_object(world, obj);
         }
     }
 
-    pub fn remove_game_object(world: &mut World, id: Entity) {
-        let data = (*world.get_component::<GameObject>(id).unwrap()).clone();
-        for child in data.children {
-            Self::remove_game_object(world, child);
+    pub fn remove_game_object(world: &mut World, id: Entity) -> Result<(), HierarchyError> {
+        Self::remove_game_object_with(world, id, &mut |_, _| ())
+    }
+
+    fn remove_game_object_with<F: FnMut(&mut World, Entity)>(
+        world: &mut World,
+        id: Entity,
+        before_removal: &mut F,
+    ) -> Result<(), HierarchyError> {
+        let children = world
+            .get_component::<GameObject>(id)
+            .map(|object| object.children.clone())
+            .ok_or_else(|| HierarchyError::missing::<GameObject>(world, id))?;
+        for child in children {
+            // callback could have removed it already
+            if world.is_alive(child) {
+                Self::remove_game_object_with(world, child, before_removal)?;
+            }
         }
-        Self::remove_single(world, id);
+        before_removal(world, id);
+        if world.is_alive(id) {
+            Self::remove_single(world, id)?;
+        }
+        Ok(())
     }
 
-    fn remove_single(world: &mut World, id: Entity) {
-        crate::hierarchy::TransformHierarchy::set_parent(world, id, None).unwrap();
+    fn remove_single(world: &mut World, id: Entity) -> Result<(), HierarchyError> {
+        crate::hierarchy::TransformHierarchy::set_parent(world, id, None)?;
+        let object = (*world.get_component::<GameObject>(id).unwrap()).clone();
+        let mut index = world.resources.get_mut::<GameObjectIndex>().unwrap();
+        index.rename(id, &object.name, "");
+        for tag in object.tags.iter() {
+            index.remove_tag(id, tag);
+        }
+        index.set_layers(id, object.layer_mask, 0);
+        drop(index);
+        // it became a root above
+        let mut roots = world.resources.get_mut::<RootObjects>().unwrap();
+        roots.0.retain(|root| *root != id);
+        drop(roots);
         world.delete(id);
+        Ok(())
     }
 
     pub fn create_empty(world: &mut World) -> Entity {
         let go = GameObject::new();
 
         let ent_id = world
-            .insert((), vec![(super::transform::Transform::new(), go)])
+            .insert(
+                (),
+                vec![(
+                    super::transform::Transform::new(),
+                    super::transform::GlobalTransform::default(),
+                    go,
+                )],
+            )
             .to_vec();
+        // new transforms are dirty
+        DirtyTransforms::mark(world, ent_id[0]);
+        world.resources.get_mut::<RootObjects>().unwrap().0.push(ent_id[0]);
+        let mut index = world.resources.get_mut::<GameObjectIndex>().unwrap();
+        index.set_layers(ent_id[0], 0, DEFAULT_LAYER_MASK);
         ent_id[0]
     }
 }
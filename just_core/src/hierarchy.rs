@@ -1,151 +1,475 @@
-use glam::Mat4;
-
 use super::game_object::GameObject;
-use super::transform::Transform;
-use crate::ecs::prelude::{Entity, World};
+use super::transform::{DirtyTransforms, GlobalTransform, Transform};
+use super::traversal::{Ancestors, BreadthFirst, DepthFirst};
+use crate::ecs::prelude::*;
 use crate::math::*;
 
+/// Events written by `TransformHierarchy` to `HierarchyChannel` resource.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HierarchyEvent {
+    ParentChanged {
+        id: Entity,
+        old_parent: Option<Entity>,
+        new_parent: Option<Entity>,
+    },
+}
+
+pub type HierarchyChannel = shrev::EventChannel<HierarchyEvent>;
+
+/// Objects without parent in their sibling order, new and unparented objects are added at the end.
+/// Kept in world resources by `GameObject::initialize`, since storage order of entities changes
+/// when components are added or removed.
+#[derive(Default)]
+pub struct RootObjects(pub(crate) Vec<Entity>);
+
+/// Errors of `TransformHierarchy` and `GameObject` operations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HierarchyError {
+    /// Entity was deleted (or never existed).
+    DeadEntity(Entity),
+    /// Entity is alive, but isn't a game object.
+    MissingComponent {
+        id: Entity,
+        component: &'static str,
+    },
+    /// New parent is a descendant of the object.
+    WouldCreateCycle {
+        id: Entity,
+        parent: Entity,
+    },
+    SelfParent(Entity),
+}
+
+impl std::fmt::Display for HierarchyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HierarchyError::DeadEntity(id) => write!(f, "object {:?} doesn't exist", id),
+            HierarchyError::MissingComponent { id, component } => {
+                write!(f, "object {:?} has no {} component", id, component)
+            }
+            HierarchyError::WouldCreateCycle { id, parent } => {
+                write!(f, "object {:?} can't be parented to its descendant {:?}", id, parent)
+            }
+            HierarchyError::SelfParent(id) => write!(f, "object {:?} can't be its own parent", id),
+        }
+    }
+}
+
+impl HierarchyError {
+    /// Error for entity lacking component `T`, dead entities lack all of them.
+    pub fn missing<T>(world: &World, id: Entity) -> Self {
+        if world.is_alive(id) {
+            let name = std::any::type_name::<T>();
+            HierarchyError::MissingComponent {
+                id,
+                component: name.rsplit("::").next().unwrap_or(name),
+            }
+        } else {
+            HierarchyError::DeadEntity(id)
+        }
+    }
+}
+
 pub struct TransformHierarchy;
 
 impl TransformHierarchy {
-    pub fn get_global_position(world: &World, id: Entity) -> Vec3 {
-        let mat = Self::get_parent_matrix(world, id);
-        let transform = world.get_component::<Transform>(id).unwrap();
-        pos(&(mat * pos_vec(&transform.position)))
+    pub fn get_global_position(world: &World, id: Entity) -> Result<Vec3, HierarchyError> {
+        Ok(pos(&Self::get_global_matrix(world, id)?.w_axis))
     }
 
-    pub fn get_global_rotation(world: &World, id: Entity) -> Quat {
-        let rotation = world.get_component::<Transform>(id).unwrap().rotation;
-        let parent = world.get_component::<GameObject>(id).unwrap().parent;
-        let parent_rotation = match parent {
+    pub fn get_global_rotation(world: &World, id: Entity) -> Result<Quat, HierarchyError> {
+        let rotation = Self::get_local_rotation(world, id)?;
+        let parent_rotation = match Self::get_parent(world, id)? {
             None => Quat::IDENTITY,
-            Some(parent_id) => Self::get_global_rotation(world, parent_id),
+            Some(parent_id) => Self::get_global_rotation(world, parent_id)?,
         };
 
-        return parent_rotation * rotation;
+        return Ok(parent_rotation * rotation);
     }
 
-    pub fn get_global_matrix(world: &World, id: Entity) -> Matrix {
-        let transform = world.get_component::<Transform>(id).unwrap();
-        let mut global_mat = transform.global_matrix.borrow_mut();
+    /// `GlobalTransform` of the object. When it or its ancestors changed since last `TransformSystem::update`
+    /// the matrix is computed from their local transforms instead, without storing it.
+    pub fn get_global_matrix(world: &World, id: Entity) -> Result<Matrix, HierarchyError> {
+        // object and its ancestors up to the root
+        let mut chain = vec![];
+        let mut topmost_dirty = None;
+        let mut current = Some(id);
+        while let Some(object) = current {
+            if Self::transform(world, object)?.dirty {
+                topmost_dirty = Some(chain.len());
+            }
+            chain.push(object);
+            current = Self::get_parent(world, object)?;
+        }
 
-        if global_mat.changed {
-            let parent_matrix = Self::get_parent_matrix(world, id);
-            let mat = parent_matrix * Self::get_local_matrix(world, id);
+        match topmost_dirty {
+            None => Self::global_transform(world, id),
+            Some(topmost) => {
+                let mut matrix = match chain.get(topmost + 1) {
+                    Some(parent) => Self::global_transform(world, *parent)?,
+                    None => Matrix::IDENTITY,
+                };
+                for object in chain[..=topmost].iter().rev() {
+                    matrix *= Self::transform(world, *object)?.local_matrix();
+                }
+                Ok(matrix)
+            }
+        }
+    }
 
-            global_mat.item = mat;
-            global_mat.changed = false;
+    fn transform(world: &World, id: Entity) -> Result<Transform, HierarchyError> {
+        world
+            .get_component::<Transform>(id)
+            .map(|transform| (*transform).clone())
+            .ok_or_else(|| HierarchyError::missing::<Transform>(world, id))
+    }
+
+    fn global_transform(world: &World, id: Entity) -> Result<Matrix, HierarchyError> {
+        world
+            .get_component::<GlobalTransform>(id)
+            .map(|global| global.0)
+            .ok_or_else(|| HierarchyError::missing::<GlobalTransform>(world, id))
+    }
+
+    /// Changes transform of the object, `TransformSystem` recomputes global matrices of it and its descendants.
+    fn modify_transform<F: FnOnce(&mut Transform)>(
+        world: &mut World,
+        id: Entity,
+        modify: F,
+    ) -> Result<(), HierarchyError> {
+        let was_dirty = world.get_component_mut::<Transform>(id).map(|mut transform| {
+            modify(&mut transform);
+            std::mem::replace(&mut transform.dirty, true)
+        });
+        match was_dirty {
+            Some(false) => DirtyTransforms::mark(world, id),
+            Some(true) => (),
+            None => return Err(HierarchyError::missing::<Transform>(world, id)),
         }
+        Ok(())
+    }
 
-        return global_mat.item;
+    pub fn set_local_position(world: &mut World, id: Entity, new_position: Vec3) -> Result<(), HierarchyError> {
+        Self::modify_transform(world, id, |transform| transform.position = new_position)
     }
 
-    fn get_parent_matrix(world: &World, id: Entity) -> Matrix {
-        let parent = world.get_component::<GameObject>(id).unwrap().parent;
-        match parent {
-            None => Matrix::IDENTITY,
-            Some(parent_id) => Self::get_global_matrix(world, parent_id),
+    pub fn set_local_rotation(world: &mut World, id: Entity, new_rotation: Quat) -> Result<(), HierarchyError> {
+        Self::modify_transform(world, id, |transform| transform.rotation = new_rotation)
+    }
+
+    pub fn set_local_scale(world: &mut World, id: Entity, new_scale: Vec3) -> Result<(), HierarchyError> {
+        Self::modify_transform(world, id, |transform| transform.scale = new_scale)
+    }
+
+    pub fn get_local_position(world: &World, id: Entity) -> Result<Vec3, HierarchyError> {
+        Ok(Self::transform(world, id)?.position)
+    }
+
+    pub fn get_local_rotation(world: &World, id: Entity) -> Result<Quat, HierarchyError> {
+        Ok(Self::transform(world, id)?.rotation)
+    }
+
+    pub fn get_local_scale(world: &World, id: Entity) -> Result<Vec3, HierarchyError> {
+        Ok(Self::transform(world, id)?.scale)
+    }
+
+    pub fn get_parent(world: &World, id: Entity) -> Result<Option<Entity>, HierarchyError> {
+        world
+            .get_component::<GameObject>(id)
+            .map(|object| object.parent)
+            .ok_or_else(|| HierarchyError::missing::<GameObject>(world, id))
+    }
+
+    /// Fails without changing anything when either object doesn't exist or `new_parent` is the object itself
+    /// or one of its descendants.
+    pub fn set_parent(world: &mut World, id: Entity, new_parent: Option<Entity>) -> Result<(), HierarchyError> {
+        let parent = Self::get_parent(world, id)?;
+        if world.get_component::<Transform>(id).is_none() {
+            return Err(HierarchyError::missing::<Transform>(world, id));
         }
+        if let Some(new_parent) = new_parent {
+            if new_parent == id {
+                return Err(HierarchyError::SelfParent(id));
+            }
+            // walking up from new parent must not reach the object
+            let mut ancestor = Self::get_parent(world, new_parent)?;
+            while let Some(current) = ancestor {
+                if current == id {
+                    return Err(HierarchyError::WouldCreateCycle { id, parent: new_parent });
+                }
+                ancestor = Self::get_parent(world, current)?;
+            }
+        }
+
+        // setting the same parent keeps the object's place among its siblings
+        if parent != new_parent {
+            Self::edit_siblings(world, parent, |siblings| siblings.retain(|y| *y != id));
+            Self::edit_siblings(world, new_parent, |siblings| siblings.push(id));
+            world.get_component_mut::<GameObject>(id).unwrap().parent = new_parent;
+        }
+        Self::modify_transform(world, id, |_| ())?;
+        GameObject::refresh_active(world, id)?;
+
+        if parent != new_parent {
+            if let Some(mut channel) = world.resources.get_mut::<HierarchyChannel>() {
+                channel.single_write(HierarchyEvent::ParentChanged {
+                    id,
+                    old_parent: parent,
+                    new_parent,
+                });
+            }
+        }
+
+        Result::Ok(())
     }
 
-    fn get_local_matrix(world: &World, id: Entity) -> Matrix {
-        let transform = world.get_component::<Transform>(id).unwrap();
-        let mut local_matrix = transform.local_matrix.borrow_mut();
+    /// Like `set_parent`, but local transform is changed so that object stays where it was in the world.
+    /// Shear that can't be represented by local transform (rotated object under non-uniformly scaled parent) is lost.
+    pub fn set_parent_keep_world(
+        world: &mut World,
+        id: Entity,
+        new_parent: Option<Entity>,
+    ) -> Result<(), HierarchyError> {
+        let global = Self::get_global_matrix(world, id)?;
+        Self::set_parent(world, id, new_parent)?;
+        let local = Self::get_parent_matrix(world, id)?.inverse() * global;
+        let (scale, rotation, position) = local.to_scale_rotation_translation();
+        Self::modify_transform(world, id, |transform| {
+            transform.position = position;
+            transform.rotation = rotation;
+            transform.scale = scale;
+        })
+    }
 
-        if local_matrix.changed {
-            local_matrix.item =
-                Mat4::from_scale_rotation_translation(transform.scale, transform.rotation, transform.position);
-            local_matrix.changed = false;
+    /// Global matrix of object's parent, identity for root objects.
+    pub fn get_parent_matrix(world: &World, id: Entity) -> Result<Matrix, HierarchyError> {
+        match Self::get_parent(world, id)? {
+            Some(parent) => Self::get_global_matrix(world, parent),
+            None => Ok(Matrix::IDENTITY),
         }
+    }
 
-        return local_matrix.item;
+    pub fn set_global_position(world: &mut World, id: Entity, new_position: Vec3) -> Result<(), HierarchyError> {
+        let position = Self::get_parent_matrix(world, id)?
+            .inverse()
+            .transform_point3(new_position);
+        Self::set_local_position(world, id, position)
     }
 
-    pub fn void_local_matrix(world: &World, id: Entity) {
-        let transform = world.get_component::<Transform>(id).unwrap();
-        transform.local_matrix.borrow_mut().changed = true;
-        drop(transform);
+    pub fn set_global_rotation(world: &mut World, id: Entity, new_rotation: Quat) -> Result<(), HierarchyError> {
+        let (_, parent_rotation, _) = Self::get_parent_matrix(world, id)?.to_scale_rotation_translation();
+        Self::set_local_rotation(world, id, parent_rotation.inverse() * new_rotation)
+    }
 
-        Self::void_global_matrix(world, id);
+    /// Scale of global matrix, exact only when there's no shear in it.
+    pub fn get_global_scale(world: &World, id: Entity) -> Result<Vec3, HierarchyError> {
+        let (scale, _, _) = Self::get_global_matrix(world, id)?.to_scale_rotation_translation();
+        Ok(scale)
     }
 
-    fn void_global_matrix(world: &World, id: Entity) {
-        let transform = world.get_component::<Transform>(id).unwrap();
-        let mut global_matrix = transform.global_matrix.borrow_mut();
-        if global_matrix.changed == true {
-            return;
-        }
-        global_matrix.changed = true;
+    /// Divides `new_scale` by parent's global scale, so like `get_global_scale` it ignores shear.
+    /// Parent scaled to zero on some axis makes any scale zero there, local scale is kept on such axes.
+    pub fn set_global_scale(world: &mut World, id: Entity, new_scale: Vec3) -> Result<(), HierarchyError> {
+        let (parent_scale, _, _) = Self::get_parent_matrix(world, id)?.to_scale_rotation_translation();
+        let local_scale = Self::get_local_scale(world, id)?;
+        let divide = |new: f32, parent: f32, local: f32| {
+            if parent.abs() <= f32::EPSILON {
+                local
+            } else {
+                new / parent
+            }
+        };
+        let scale = Vec3::new(
+            divide(new_scale.x, parent_scale.x, local_scale.x),
+            divide(new_scale.y, parent_scale.y, local_scale.y),
+            divide(new_scale.z, parent_scale.z, local_scale.z),
+        );
+        Self::set_local_scale(world, id, scale)
+    }
 
-        for child in world.get_component::<GameObject>(id).unwrap().children.iter() {
-            Self::void_global_matrix(world, *child);
+    /// Rotates object so that its forward axis (+Z) points at `target`, given in world space.
+    /// Does nothing when object is already at `target`.
+    pub fn look_at(world: &mut World, id: Entity, target: Vec3, up: Vec3) -> Result<(), HierarchyError> {
+        let direction = target - Self::get_global_position(world, id)?;
+        if direction.length_squared() <= f32::EPSILON {
+            return Ok(());
         }
+        Self::set_global_rotation(world, id, look_rotation(direction, up))
     }
 
-    pub fn set_local_position(world: &mut World, id: Entity, new_position: Vec3) {
-        let mut transform = world.get_component_mut::<Transform>(id).unwrap();
-        transform.position = new_position;
-        drop(transform);
-        Self::void_local_matrix(world, id);
+    /// Converts point from object's local space to world space.
+    pub fn transform_point(world: &World, id: Entity, point: Vec3) -> Result<Vec3, HierarchyError> {
+        Ok(Self::get_global_matrix(world, id)?.transform_point3(point))
     }
 
-    pub fn set_local_rotation(world: &mut World, id: Entity, new_rotation: Quat) {
-        let mut transform = world.get_component_mut::<Transform>(id).unwrap();
-        transform.rotation = new_rotation;
-        drop(transform);
-        Self::void_local_matrix(world, id);
+    /// Converts point from world space to object's local space.
+    pub fn inverse_transform_point(world: &World, id: Entity, point: Vec3) -> Result<Vec3, HierarchyError> {
+        Ok(Self::get_global_matrix(world, id)?.inverse().transform_point3(point))
     }
 
-    pub fn set_local_scale(world: &mut World, id: Entity, new_scale: Vec3) {
-        let mut transform = world.get_component_mut::<Transform>(id).unwrap();
-        transform.scale = new_scale;
-        drop(transform);
-        Self::void_local_matrix(world, id);
+    /// Converts direction from object's local space to world space. Only rotation is applied,
+    /// so length of the direction is kept.
+    pub fn transform_direction(world: &World, id: Entity, direction: Vec3) -> Result<Vec3, HierarchyError> {
+        let (_, rotation, _) = Self::get_global_matrix(world, id)?.to_scale_rotation_translation();
+        Ok(rotation * direction)
     }
 
-    pub fn get_local_position(world: &World, id: Entity) -> Vec3 {
-        let transform = world.get_component::<Transform>(id).unwrap();
-        return transform.position;
+    /// Converts direction from world space to object's local space, see `transform_direction`.
+    pub fn inverse_transform_direction(world: &World, id: Entity, direction: Vec3) -> Result<Vec3, HierarchyError> {
+        let (_, rotation, _) = Self::get_global_matrix(world, id)?.to_scale_rotation_translation();
+        Ok(rotation.inverse() * direction)
     }
 
-    pub fn get_local_rotation(world: &World, id: Entity) -> Quat {
-        let transform = world.get_component::<Transform>(id).unwrap();
-        return transform.rotation;
+    /// Descendants of the object in pre-order: every object is followed by its descendants,
+    /// children come in sibling order. Object itself isn't included.
+    pub fn descendants_depth_first(world: &World, id: Entity) -> Result<DepthFirst<'_>, HierarchyError> {
+        Self::get_parent(world, id)?;
+        Ok(DepthFirst::new(world, id))
     }
 
-    pub fn get_local_scale(world: &World, id: Entity) -> Vec3 {
-        let transform = world.get_component::<Transform>(id).unwrap();
-        return transform.scale;
+    /// Descendants of the object, children first, then grandchildren and so on.
+    pub fn descendants_breadth_first(world: &World, id: Entity) -> Result<BreadthFirst<'_>, HierarchyError> {
+        Self::get_parent(world, id)?;
+        Ok(BreadthFirst::new(world, id))
     }
 
-    pub fn get_parent(world: &World, id: Entity) -> Option<Entity> {
-        world.get_component::<GameObject>(id).unwrap().parent
+    /// Parent of the object, its parent and so on up to the root.
+    pub fn ancestors(world: &World, id: Entity) -> Result<Ancestors<'_>, HierarchyError> {
+        Self::get_parent(world, id)?;
+        Ok(Ancestors::new(world, id))
     }
 
-    pub fn set_parent(world: &mut World, id: Entity, new_parent: Option<Entity>) -> Result<(), ()> {
-        if !world.is_alive(id) {
-            return Result::Err(());
-        }
-        if let Some(x) = new_parent {
-            if !world.is_alive(x) {
-                return Result::Err(());
+    /// Objects without parent, in their sibling order.
+    pub fn roots(world: &World) -> Vec<Entity> {
+        let roots = world.resources.get::<RootObjects>().unwrap();
+        roots.0.iter().copied().filter(|id| world.is_alive(*id)).collect()
+    }
+
+    /// Runs `f` on children of `parent`, or on roots when it's `None`.
+    fn edit_siblings<F: FnOnce(&mut Vec<Entity>)>(world: &mut World, parent: Option<Entity>, f: F) {
+        match parent {
+            Some(parent) => {
+                if let Some(mut object) = world.get_component_mut::<GameObject>(parent) {
+                    f(&mut object.children);
+                }
             }
-            world.get_component_mut::<GameObject>(x).unwrap().children.push(id);
+            None => f(&mut world.resources.get_mut::<RootObjects>().unwrap().0),
         }
-        let parent = Self::get_parent(world, id);
-        match parent {
-            None => (),
-            Some(x) => {
-                let mut data = world.get_component_mut::<GameObject>(x).unwrap();
-                let index = data.children.iter().position(|y| *y == id).unwrap();
-                data.children.remove(index);
+    }
+
+    /// Children of object's parent in sibling order, roots for root objects. Object itself is included.
+    fn siblings_with(world: &World, id: Entity) -> Result<Vec<Entity>, HierarchyError> {
+        Ok(match Self::get_parent(world, id)? {
+            Some(parent) => world.get_component::<GameObject>(parent).unwrap().children.clone(),
+            None => Self::roots(world),
+        })
+    }
+
+    /// Other children of object's parent in sibling order, other roots for root objects.
+    pub fn siblings(world: &World, id: Entity) -> Result<Vec<Entity>, HierarchyError> {
+        let siblings = Self::siblings_with(world, id)?;
+        Ok(siblings.into_iter().filter(|sibling| *sibling != id).collect())
+    }
+
+    /// Position of the object among children of its parent, or among roots.
+    pub fn get_sibling_index(world: &World, id: Entity) -> Result<usize, HierarchyError> {
+        let siblings = Self::siblings_with(world, id)?;
+        Ok(siblings.iter().position(|sibling| *sibling == id).unwrap())
+    }
+
+    /// Moves the object to `index` among children of its parent (or among roots),
+    /// indices past the end move it to the end.
+    pub fn set_sibling_index(world: &mut World, id: Entity, index: usize) -> Result<(), HierarchyError> {
+        Self::reorder(world, id, |_| index)
+    }
+
+    /// Places the object right before `sibling`, first moving it to parent of `sibling` when it has other parent
+    /// (or making it a root when `sibling` is one).
+    pub fn move_before(world: &mut World, id: Entity, sibling: Entity) -> Result<(), HierarchyError> {
+        Self::move_next_to(world, id, sibling, 0)
+    }
+
+    /// Places the object right after `sibling`, first moving it to parent of `sibling` when it has other parent
+    /// (or making it a root when `sibling` is one).
+    pub fn move_after(world: &mut World, id: Entity, sibling: Entity) -> Result<(), HierarchyError> {
+        Self::move_next_to(world, id, sibling, 1)
+    }
+
+    fn move_next_to(world: &mut World, id: Entity, sibling: Entity, offset: usize) -> Result<(), HierarchyError> {
+        let parent = Self::get_parent(world, id)?;
+        let sibling_parent = Self::get_parent(world, sibling)?;
+        if id == sibling {
+            return Ok(());
+        }
+        if parent != sibling_parent {
+            Self::set_parent(world, id, sibling_parent)?;
+        }
+        Self::reorder(world, id, |children| {
+            children
+                .iter()
+                .position(|child| *child == sibling)
+                .map_or(children.len(), |index| index + offset)
+        })
+    }
+
+    /// Moves the object to index returned by `index` for its siblings without the object.
+    fn reorder<F: FnOnce(&[Entity]) -> usize>(world: &mut World, id: Entity, index: F) -> Result<(), HierarchyError> {
+        let parent = Self::get_parent(world, id)?;
+        Self::edit_siblings(world, parent, |siblings| {
+            siblings.retain(|sibling| *sibling != id);
+            let index = index(siblings).min(siblings.len());
+            siblings.insert(index, id);
+        });
+        Ok(())
+    }
+
+    /// First child of the object with given name.
+    pub fn find_child(world: &World, id: Entity, name: &str) -> Result<Option<Entity>, HierarchyError> {
+        let children = world
+            .get_component::<GameObject>(id)
+            .map(|object| object.children.clone())
+            .ok_or_else(|| HierarchyError::missing::<GameObject>(world, id))?;
+        Ok(children.into_iter().find(|child| Self::has_name(world, *child, name)))
+    }
+
+    /// Resolves path of names separated by `/` starting at a root object, e.g. `"Level/Door/Hinge"`.
+    /// When several objects on the way have the same name, all of them are searched in sibling order.
+    pub fn find_path(world: &World, path: &str) -> Option<Entity> {
+        let names: Vec<&str> = path.split('/').filter(|name| !name.is_empty()).collect();
+        Self::resolve_path(world, Self::roots(world), &names)
+    }
+
+    /// Like `find_path`, but path starts at children of the object.
+    pub fn find_path_from(world: &World, id: Entity, path: &str) -> Result<Option<Entity>, HierarchyError> {
+        let names: Vec<&str> = path.split('/').filter(|name| !name.is_empty()).collect();
+        let children = world
+            .get_component::<GameObject>(id)
+            .map(|object| object.children.clone())
+            .ok_or_else(|| HierarchyError::missing::<GameObject>(world, id))?;
+        Ok(Self::resolve_path(world, children, &names))
+    }
+
+    fn resolve_path(world: &World, candidates: Vec<Entity>, names: &[&str]) -> Option<Entity> {
+        let (name, rest) = names.split_first()?;
+        for candidate in candidates {
+            if !Self::has_name(world, candidate, name) {
+                continue;
+            }
+            if rest.is_empty() {
+                return Some(candidate);
+            }
+            let children = world.get_component::<GameObject>(candidate).unwrap().children.clone();
+            if let Some(found) = Self::resolve_path(world, children, rest) {
+                return Some(found);
             }
         }
-        let mut data = world.get_component_mut::<GameObject>(id).unwrap();
-        data.parent = new_parent;
-        drop(data);
-        Self::void_local_matrix(world, id);
+        None
+    }
 
-        Result::Ok(())
+    fn has_name(world: &World, id: Entity, name: &str) -> bool {
+        world
+            .get_component::<GameObject>(id)
+            .map(|object| object.name == name)
+            .unwrap_or(false)
     }
 }
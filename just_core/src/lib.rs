@@ -1,7 +1,11 @@
+pub mod commands;
+pub mod events;
 pub mod game_object;
 pub mod hierarchy;
 pub mod math;
+pub mod object_index;
 pub mod transform;
+pub mod traversal;
 
 pub use legion as ecs;
 pub use shrev;
@@ -14,8 +18,3 @@ pub use glam;
 pub struct GameObjectData {
     pub id: ecs::entity::Entity,
 }
-
-#[derive(Debug, Clone, Default)]
-pub struct RenderableCreationQueue {
-    pub queue: Vec<(ecs::entity::Entity, String, String)>,
-}
@@ -20,3 +20,22 @@ pub fn dir_vec(pos: &Vec3) -> Vec4 {
 pub fn pos(vec: &Vec4) -> Vec3 {
     vec.xyz()
 }
+
+/// Rotation turning forward axis (+Z) towards `direction`, with up axis as close to `up` as possible.
+/// Same basis as `Matrix::look_at_lh` uses, but for objects instead of a view.
+/// When `up` is parallel to `direction` (or zero), +Y or +Z is used instead. Zero direction gives no rotation.
+pub fn look_rotation(direction: Vec3, up: Vec3) -> Quat {
+    let forward = direction.normalize_or_zero();
+    if forward == Vec3::ZERO {
+        return Quat::IDENTITY;
+    }
+    // +Y and +Z can't both be parallel to forward
+    let right = [up.normalize_or_zero(), Vec3::Y, Vec3::Z]
+        .iter()
+        .map(|up| up.cross(forward))
+        .find(|right| right.length_squared() > 1e-6)
+        .unwrap()
+        .normalize();
+    let up = forward.cross(right);
+    Quat::from_mat3(&Matrix3::from_cols(right, up, forward))
+}
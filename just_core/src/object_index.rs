@@ -0,0 +1,98 @@
+use crate::ecs::prelude::*;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+/// Entities in insertion order, removal swaps the last one into the freed place.
+/// All operations are constant time, so large groups (e.g. default layer) stay cheap to maintain.
+#[derive(Default)]
+struct EntitySet {
+    entities: Vec<Entity>,
+    positions: HashMap<Entity, usize>,
+}
+
+impl EntitySet {
+    fn insert(&mut self, id: Entity) {
+        if let Entry::Vacant(entry) = self.positions.entry(id) {
+            entry.insert(self.entities.len());
+            self.entities.push(id);
+        }
+    }
+
+    fn remove(&mut self, id: Entity) {
+        if let Some(position) = self.positions.remove(&id) {
+            self.entities.swap_remove(position);
+            if let Some(moved) = self.entities.get(position) {
+                self.positions.insert(*moved, position);
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
+
+/// Game objects by name, tag and layer, kept up to date by `GameObject` methods. Stored in world resources.
+#[derive(Default)]
+pub struct GameObjectIndex {
+    names: HashMap<String, EntitySet>,
+    tags: HashMap<String, EntitySet>,
+    layers: [EntitySet; 32],
+}
+
+impl GameObjectIndex {
+    /// Objects named `name`, unnamed objects aren't indexed.
+    pub fn by_name(&self, name: &str) -> &[Entity] {
+        self.names.get(name).map(|set| set.entities.as_slice()).unwrap_or(&[])
+    }
+
+    pub fn by_tag(&self, tag: &str) -> &[Entity] {
+        self.tags.get(tag).map(|set| set.entities.as_slice()).unwrap_or(&[])
+    }
+
+    /// Objects whose layer mask has bit `layer` set, nothing for layers past 31.
+    pub fn in_layer(&self, layer: u32) -> &[Entity] {
+        self.layers
+            .get(layer as usize)
+            .map(|set| set.entities.as_slice())
+            .unwrap_or(&[])
+    }
+
+    pub(crate) fn rename(&mut self, id: Entity, old_name: &str, new_name: &str) {
+        if !old_name.is_empty() {
+            Self::remove_from(&mut self.names, old_name, id);
+        }
+        if !new_name.is_empty() {
+            self.names.entry(new_name.to_owned()).or_default().insert(id);
+        }
+    }
+
+    pub(crate) fn add_tag(&mut self, id: Entity, tag: &str) {
+        self.tags.entry(tag.to_owned()).or_default().insert(id);
+    }
+
+    pub(crate) fn remove_tag(&mut self, id: Entity, tag: &str) {
+        Self::remove_from(&mut self.tags, tag, id);
+    }
+
+    pub(crate) fn set_layers(&mut self, id: Entity, old_mask: u32, new_mask: u32) {
+        for (layer, set) in self.layers.iter_mut().enumerate() {
+            let bit = 1 << layer;
+            match (old_mask & bit != 0, new_mask & bit != 0) {
+                (false, true) => set.insert(id),
+                (true, false) => set.remove(id),
+                _ => (),
+            }
+        }
+    }
+
+    /// Empty groups are dropped, so objects with unique names don't leave entries behind.
+    fn remove_from(groups: &mut HashMap<String, EntitySet>, key: &str, id: Entity) {
+        if let Some(set) = groups.get_mut(key) {
+            set.remove(id);
+            if set.is_empty() {
+                groups.remove(key);
+            }
+        }
+    }
+}
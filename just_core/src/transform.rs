@@ -1,59 +1,161 @@
+use crate::ecs::prelude::*;
+use crate::game_object::GameObject;
 use crate::math::*;
-use std::cell::RefCell;
+use std::collections::HashMap;
 
-pub trait Ident {
-    fn empty() -> Self;
+/// Local transform of game object, relative to its parent.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transform {
+    pub position: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+    /// Set when transform or parent of the object changes, `GlobalTransform` of the object
+    /// and its descendants is stale until `TransformSystem::update` clears it.
+    pub(crate) dirty: bool,
 }
 
-impl Ident for Matrix {
-    fn empty() -> Self {
-        Matrix::IDENTITY
+impl Transform {
+    pub fn new() -> Self {
+        Transform {
+            position: Vec3::ZERO,
+            scale: Vec3::new(1.0, 1.0, 1.0),
+            rotation: Quat::IDENTITY,
+            dirty: true,
+        }
+    }
+
+    pub fn local_matrix(&self) -> Matrix {
+        Matrix::from_scale_rotation_translation(self.scale, self.rotation, self.position)
     }
 }
 
-impl Ident for Quat {
-    fn empty() -> Self {
-        Quat::IDENTITY
+/// Objects whose `Transform` became dirty since last `TransformSystem::update`, so it doesn't have to
+/// look at all of them. Kept in world resources by `GameObject::initialize`.
+#[derive(Default)]
+pub struct DirtyTransforms(Vec<Entity>);
+
+impl DirtyTransforms {
+    /// Remembers object whose transform was clean until now.
+    pub(crate) fn mark(world: &World, id: Entity) {
+        world.resources.get_mut::<DirtyTransforms>().unwrap().0.push(id);
     }
 }
 
+/// World matrix of game object, written by `TransformSystem` once per frame.
 #[derive(Copy, Clone, Debug, PartialEq)]
-pub struct ItemState<T: Ident> {
-    pub changed: bool,
-    pub item: T,
+pub struct GlobalTransform(pub Matrix);
+
+impl Default for GlobalTransform {
+    fn default() -> Self {
+        GlobalTransform(Matrix::IDENTITY)
+    }
+}
+
+/// Where global matrix of object's parent comes from during propagation.
+#[derive(Copy, Clone)]
+enum ParentMatrix {
+    /// Parent wasn't changed, its `GlobalTransform` is up to date.
+    Clean(Matrix),
+    /// Index of parent in the batch, it's always computed before its children.
+    Batched(usize),
+}
+
+struct Node {
+    id: Entity,
+    parent: ParentMatrix,
+    local: Matrix,
 }
 
-pub type MatrixState = ItemState<Matrix>;
+pub struct TransformSystem;
 
-impl<T: Ident> ItemState<T> {
-    fn new() -> Self {
-        Self {
-            changed: true,
-            item: T::empty(),
+impl TransformSystem {
+    /// Propagates transforms changed since last update down the hierarchy and writes `GlobalTransform`s.
+    /// Subtrees of dirty roots (dirty objects without dirty ancestors) are flattened in topological
+    /// order first, so matrices are multiplied in one pass over contiguous memory and every changed
+    /// object is computed once, no matter how many of its ancestors changed.
+    pub fn update(world: &mut World) {
+        let marked = std::mem::take(&mut world.resources.get_mut::<DirtyTransforms>().unwrap().0);
+        // objects removed since, or already computed as descendants of other dirty objects
+        let dirty: Vec<Entity> = marked
+            .into_iter()
+            .filter(|id| world.get_component::<Transform>(*id).map(|t| t.dirty).unwrap_or(false))
+            .collect();
+        if dirty.is_empty() {
+            return;
+        }
+
+        let nodes = Self::flatten(world, &dirty);
+
+        let mut globals: Vec<Matrix> = Vec::with_capacity(nodes.len());
+        for node in nodes.iter() {
+            let parent = match node.parent {
+                ParentMatrix::Clean(matrix) => matrix,
+                ParentMatrix::Batched(index) => globals[index],
+            };
+            globals.push(parent * node.local);
+        }
+
+        for (node, global) in nodes.iter().zip(globals.into_iter()) {
+            *world.get_component_mut::<GlobalTransform>(node.id).unwrap() = GlobalTransform(global);
+            world.get_component_mut::<Transform>(node.id).unwrap().dirty = false;
         }
     }
-}
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct Transform {
-    pub position: Vec3,
-    pub rotation: Quat,
-    pub scale: Vec3,
-    pub local_matrix: RefCell<MatrixState>,
-    pub global_matrix: RefCell<MatrixState>,
-}
+    /// Subtrees of dirty roots, every parent is placed before its children.
+    fn flatten(world: &World, dirty: &[Entity]) -> Vec<Node> {
+        // whether object is below a dirty one, remembered for every visited ancestor,
+        // so each object is visited once even in deep hierarchies
+        let mut below_dirty: HashMap<Entity, bool> = dirty.iter().map(|id| (*id, true)).collect();
+        let mut nodes: Vec<Node> = vec![];
+        for id in dirty.iter().copied() {
+            let parent = world.get_component::<GameObject>(id).unwrap().parent;
+            if Self::is_below_dirty(world, &mut below_dirty, parent) {
+                continue;
+            }
+            let parent_matrix = match parent {
+                Some(parent) => world.get_component::<GlobalTransform>(parent).unwrap().0,
+                None => Matrix::IDENTITY,
+            };
+            nodes.push(Node {
+                id,
+                parent: ParentMatrix::Clean(parent_matrix),
+                local: world.get_component::<Transform>(id).unwrap().local_matrix(),
+            });
+        }
 
-unsafe impl Send for Transform {}
-unsafe impl Sync for Transform {}
+        let mut next = 0;
+        while next < nodes.len() {
+            let object = world.get_component::<GameObject>(nodes[next].id).unwrap();
+            for child in object.children.iter().copied() {
+                nodes.push(Node {
+                    id: child,
+                    parent: ParentMatrix::Batched(next),
+                    local: world.get_component::<Transform>(child).unwrap().local_matrix(),
+                });
+            }
+            next += 1;
+        }
+        nodes
+    }
 
-impl Transform {
-    pub fn new() -> Self {
-        Transform {
-            position: Vec3::ZERO,
-            scale: Vec3::new(1.0, 1.0, 1.0),
-            rotation: Quat::IDENTITY,
-            local_matrix: RefCell::new(MatrixState::new()),
-            global_matrix: RefCell::new(MatrixState::new()),
+    /// Whether `id` or any of its ancestors is dirty.
+    fn is_below_dirty(world: &World, below_dirty: &mut HashMap<Entity, bool>, id: Option<Entity>) -> bool {
+        let mut visited = vec![];
+        let mut current = id;
+        let result = loop {
+            let object = match current {
+                Some(object) => object,
+                None => break false,
+            };
+            if let Some(known) = below_dirty.get(&object) {
+                break *known;
+            }
+            visited.push(object);
+            current = world.get_component::<GameObject>(object).unwrap().parent;
+        };
+        for object in visited {
+            below_dirty.insert(object, result);
         }
+        result
     }
 }
@@ -0,0 +1,88 @@
+use crate::ecs::prelude::*;
+use crate::game_object::GameObject;
+use std::collections::VecDeque;
+
+fn children(world: &World, id: Entity) -> Vec<Entity> {
+    world
+        .get_component::<GameObject>(id)
+        .map(|object| object.children.clone())
+        .unwrap_or_default()
+}
+
+fn parent(world: &World, id: Entity) -> Option<Entity> {
+    world.get_component::<GameObject>(id).and_then(|object| object.parent)
+}
+
+/// Descendants in pre-order, children in their sibling order. Created by `TransformHierarchy::descendants_depth_first`.
+pub struct DepthFirst<'a> {
+    world: &'a World,
+    stack: Vec<Entity>,
+}
+
+impl<'a> DepthFirst<'a> {
+    pub(crate) fn new(world: &'a World, id: Entity) -> Self {
+        let mut stack = children(world, id);
+        stack.reverse();
+        DepthFirst { world, stack }
+    }
+}
+
+impl<'a> Iterator for DepthFirst<'a> {
+    type Item = Entity;
+
+    fn next(&mut self) -> Option<Entity> {
+        let id = self.stack.pop()?;
+        self.stack.extend(children(self.world, id).into_iter().rev());
+        Some(id)
+    }
+}
+
+/// Descendants level by level, created by `TransformHierarchy::descendants_breadth_first`.
+pub struct BreadthFirst<'a> {
+    world: &'a World,
+    queue: VecDeque<Entity>,
+}
+
+impl<'a> BreadthFirst<'a> {
+    pub(crate) fn new(world: &'a World, id: Entity) -> Self {
+        BreadthFirst {
+            world,
+            queue: children(world, id).into(),
+        }
+    }
+}
+
+impl<'a> Iterator for BreadthFirst<'a> {
+    type Item = Entity;
+
+    fn next(&mut self) -> Option<Entity> {
+        let id = self.queue.pop_front()?;
+        self.queue.extend(children(self.world, id));
+        Some(id)
+    }
+}
+
+/// Parent, its parent and so on up to the root, created by `TransformHierarchy::ancestors`.
+pub struct Ancestors<'a> {
+    world: &'a World,
+    current: Option<Entity>,
+}
+
+impl<'a> Ancestors<'a> {
+    pub(crate) fn new(world: &'a World, id: Entity) -> Self {
+        Ancestors {
+            world,
+            current: parent(world, id),
+        }
+    }
+}
+
+impl<'a> Iterator for Ancestors<'a> {
+    type Item = Entity;
+
+    fn next(&mut self) -> Option<Entity> {
+        let id = self.current?;
+        self.current = parent(self.world, id);
+        Some(id)
+    }
+}
@@ -0,0 +1,100 @@
+//! Checks applying `Commands` and resolving placeholders of objects they create.
+
+use just_core::commands::{CommandError, Commands, Target};
+use just_core::ecs::prelude::*;
+use just_core::game_object::GameObject;
+use just_core::hierarchy::TransformHierarchy;
+
+fn world() -> World {
+    let mut world = World::default();
+    GameObject::initialize(&mut world);
+    world
+}
+
+fn record<F: FnOnce(&mut Commands) -> R, R>(world: &World, f: F) -> R {
+    let mut commands = world.resources.get_mut::<Commands>().unwrap();
+    f(&mut commands)
+}
+
+/// Stand-in for renderable, keeps mesh and texture names.
+#[derive(Debug, Clone, PartialEq)]
+struct Names(String, String);
+
+fn create_names(world: &mut World, id: Entity, mesh: &str, texture: &str) {
+    world.add_component(id, Names(mesh.to_owned(), texture.to_owned()));
+}
+
+#[test]
+fn created_objects_are_resolved() {
+    let mut world = world();
+    let (parent, child) = record(&world, |commands| {
+        let parent = commands.create();
+        let child = commands.create();
+        commands.rename(parent, "parent");
+        commands.set_parent(child, Some(parent.into()));
+        (parent, child)
+    });
+    assert_eq!(record(&world, |commands| commands.resolve(parent)), None);
+
+    assert!(Commands::apply(&mut world).is_empty());
+    let parent = record(&world, |commands| commands.resolve(parent)).unwrap();
+    let child = record(&world, |commands| commands.resolve(child)).unwrap();
+    assert_eq!(GameObject::find_by_name(&world, "parent"), vec![parent]);
+    assert_eq!(TransformHierarchy::get_parent(&world, child), Ok(Some(parent)));
+}
+
+#[test]
+fn placeholders_are_resolved_across_applies() {
+    let mut world = world();
+    let parent = record(&world, |commands| commands.create());
+    Commands::apply(&mut world);
+    // two more sync points before the placeholder is used again
+    Commands::apply(&mut world);
+    let child = GameObject::create_empty(&mut world);
+    record(&world, |commands| commands.rename(child, "child"));
+    Commands::apply(&mut world);
+
+    record(&world, |commands| commands.set_parent(child, Some(parent.into())));
+    assert!(Commands::apply(&mut world).is_empty());
+    let parent_id = record(&world, |commands| commands.resolve(parent)).unwrap();
+    assert_eq!(TransformHierarchy::get_parent(&world, child), Ok(Some(parent_id)));
+
+    // forgotten once the object is removed
+    GameObject::remove_game_object(&mut world, parent_id).unwrap();
+    record(&world, |commands| {
+        commands.set_parent(child, Some(Target::Placeholder(parent)))
+    });
+    assert_eq!(Commands::apply(&mut world), vec![CommandError::Unresolved(parent)]);
+    assert_eq!(record(&world, |commands| commands.resolve(parent)), None);
+}
+
+#[test]
+fn renderables_are_created_by_registered_creator() {
+    let mut world = world();
+    let id = GameObject::create_empty(&mut world);
+    let placeholder = record(&world, |commands| {
+        commands.add_renderable(id, "cube", "stone");
+        commands.create()
+    });
+    assert_eq!(Commands::apply(&mut world), vec![CommandError::NoRenderableCreator]);
+
+    record(&world, |commands| {
+        commands.register_renderable_creator(create_names);
+        commands.add_renderable(id, "cube", "stone");
+        commands.add_renderable(placeholder, "sphere", "wood");
+        assert_eq!(commands.queued_renderable(id), Some(("cube", "stone")));
+        let (mesh, _) = commands.queued_renderable_mut(id).unwrap();
+        *mesh = "cone".to_owned();
+    });
+    assert!(Commands::apply(&mut world).is_empty());
+    let created = record(&world, |commands| commands.resolve(placeholder)).unwrap();
+    assert_eq!(
+        *world.get_component::<Names>(id).unwrap(),
+        Names("cone".to_owned(), "stone".to_owned())
+    );
+    assert_eq!(
+        *world.get_component::<Names>(created).unwrap(),
+        Names("sphere".to_owned(), "wood".to_owned())
+    );
+    assert!(record(&world, |commands| commands.queued_renderable(id).is_none()));
+}
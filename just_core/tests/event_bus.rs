@@ -0,0 +1,112 @@
+//! Checks declaring, publishing and reading events of `EventBus`.
+
+use just_core::events::{Event, EventBus, EventError};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Damage {
+    target: String,
+    amount: u32,
+}
+
+impl Event for Damage {
+    const NAME: &'static str = "damage";
+}
+
+#[test]
+fn events_have_to_be_declared() {
+    let mut bus = EventBus::default();
+    assert_eq!(
+        bus.publish("unknown", json!(null)),
+        Err(EventError::Undeclared("unknown".to_owned()))
+    );
+    assert!(bus.register_reader("unknown").is_err());
+
+    bus.declare("b");
+    bus.declare("a");
+    bus.declare("b");
+    assert!(bus.is_declared("a"));
+    assert_eq!(bus.names(), vec!["a", "b"]);
+}
+
+#[test]
+fn readers_get_events_published_after_registering() {
+    let mut bus = EventBus::default();
+    bus.declare("score");
+    bus.publish("score", json!(1)).unwrap();
+    let mut first = bus.register_reader("score").unwrap();
+    bus.publish("score", json!(2)).unwrap();
+    let mut second = bus.register_reader("score").unwrap();
+    bus.publish("score", json!({ "points": 3 })).unwrap();
+
+    let read: Vec<_> = bus.read(&mut first).cloned().collect();
+    assert_eq!(read, vec![json!(2), json!({ "points": 3 })]);
+    let read: Vec<_> = bus.read(&mut second).cloned().collect();
+    assert_eq!(read, vec![json!({ "points": 3 })]);
+    assert_eq!(bus.read(&mut first).count(), 0);
+    assert_eq!(first.name(), "score");
+}
+
+#[test]
+fn payloads_of_typed_events_are_validated() {
+    let mut bus = EventBus::default();
+    bus.declare_type::<Damage>();
+    let mut reader = bus.register_reader(Damage::NAME).unwrap();
+
+    let invalid = bus.publish(Damage::NAME, json!({ "target": "player" }));
+    assert!(matches!(invalid, Err(EventError::InvalidPayload { ref name, .. }) if name == "damage"));
+    bus.publish(Damage::NAME, json!({ "target": "player", "amount": 5 }))
+        .unwrap();
+    bus.send(&Damage {
+        target: "enemy".to_owned(),
+        amount: 2,
+    })
+    .unwrap();
+
+    let read: Vec<_> = bus
+        .read_typed::<Damage>(&mut reader)
+        .into_iter()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(
+        read,
+        vec![
+            Damage {
+                target: "player".to_owned(),
+                amount: 5
+            },
+            Damage {
+                target: "enemy".to_owned(),
+                amount: 2
+            },
+        ]
+    );
+}
+
+#[test]
+fn events_declared_by_name_can_get_type_later() {
+    let mut bus = EventBus::default();
+    bus.declare(Damage::NAME);
+    let mut reader = bus.register_reader(Damage::NAME).unwrap();
+    bus.publish(Damage::NAME, json!("anything")).unwrap();
+    bus.declare_type::<Damage>();
+    assert!(bus.publish(Damage::NAME, json!("anything")).is_err());
+
+    // published before the type was declared
+    let read = bus.read_typed::<Damage>(&mut reader);
+    assert_eq!(read.len(), 1);
+    assert!(matches!(read[0], Err(EventError::InvalidPayload { .. })));
+}
+
+#[test]
+fn sending_declares_type() {
+    let mut bus = EventBus::default();
+    let damage = Damage {
+        target: "player".to_owned(),
+        amount: 1,
+    };
+    bus.send(&damage).unwrap();
+    assert!(bus.is_declared(Damage::NAME));
+    assert!(bus.publish(Damage::NAME, json!(1)).is_err());
+}
@@ -0,0 +1,232 @@
+//! Checks reparenting of game objects and transforms relative to parents.
+
+use just_core::ecs::prelude::*;
+use just_core::game_object::GameObject;
+use just_core::hierarchy::{HierarchyError, TransformHierarchy};
+use just_core::math::Vec3;
+
+fn world() -> World {
+    let mut world = World::default();
+    GameObject::initialize(&mut world);
+    world
+}
+
+fn children(world: &World, id: Entity) -> Vec<Entity> {
+    world.get_component::<GameObject>(id).unwrap().children.clone()
+}
+
+/// Objects `a -> b -> c`, each child of the previous one.
+fn chain(world: &mut World) -> [Entity; 3] {
+    let a = GameObject::create_empty(world);
+    let b = GameObject::create_empty(world);
+    let c = GameObject::create_empty(world);
+    TransformHierarchy::set_parent(world, b, Some(a)).unwrap();
+    TransformHierarchy::set_parent(world, c, Some(b)).unwrap();
+    [a, b, c]
+}
+
+#[test]
+fn parenting_to_descendant_is_rejected() {
+    let mut world = world();
+    let [a, b, c] = chain(&mut world);
+
+    assert_eq!(
+        TransformHierarchy::set_parent(&mut world, a, Some(c)),
+        Err(HierarchyError::WouldCreateCycle { id: a, parent: c })
+    );
+    assert_eq!(
+        TransformHierarchy::set_parent(&mut world, a, Some(b)),
+        Err(HierarchyError::WouldCreateCycle { id: a, parent: b })
+    );
+    // nothing changed
+    assert_eq!(TransformHierarchy::get_parent(&world, a), Ok(None));
+    assert_eq!(children(&world, a), vec![b]);
+    assert_eq!(children(&world, b), vec![c]);
+    assert_eq!(children(&world, c), vec![]);
+}
+
+#[test]
+fn parenting_to_itself_is_rejected() {
+    let mut world = world();
+    let [_, b, c] = chain(&mut world);
+
+    assert_eq!(
+        TransformHierarchy::set_parent(&mut world, b, Some(b)),
+        Err(HierarchyError::SelfParent(b))
+    );
+    assert_eq!(children(&world, b), vec![c]);
+}
+
+#[test]
+fn reparenting_within_chain_is_allowed() {
+    let mut world = world();
+    let [a, b, c] = chain(&mut world);
+
+    TransformHierarchy::set_parent(&mut world, c, Some(a)).unwrap();
+    assert_eq!(children(&world, a), vec![b, c]);
+    assert_eq!(children(&world, b), vec![]);
+    TransformHierarchy::set_parent(&mut world, a, Some(b)).unwrap_err();
+    TransformHierarchy::set_parent(&mut world, b, None).unwrap();
+    TransformHierarchy::set_parent(&mut world, a, Some(b)).unwrap();
+    assert_eq!(TransformHierarchy::get_parent(&world, a), Ok(Some(b)));
+}
+
+#[test]
+fn parenting_to_dead_object_is_rejected() {
+    let mut world = world();
+    let [a, b, _] = chain(&mut world);
+    let dead = GameObject::create_empty(&mut world);
+    world.delete(dead);
+
+    assert_eq!(
+        TransformHierarchy::set_parent(&mut world, b, Some(dead)),
+        Err(HierarchyError::DeadEntity(dead))
+    );
+    assert_eq!(
+        TransformHierarchy::set_parent(&mut world, dead, Some(a)),
+        Err(HierarchyError::DeadEntity(dead))
+    );
+    assert_eq!(TransformHierarchy::get_parent(&world, b), Ok(Some(a)));
+}
+
+#[test]
+fn global_scale_under_parent_scaled_to_zero_keeps_local_scale() {
+    let mut world = world();
+    let [a, b, _] = chain(&mut world);
+    TransformHierarchy::set_local_scale(&mut world, a, Vec3::new(0.0, 2.0, 1.0)).unwrap();
+    TransformHierarchy::set_local_scale(&mut world, b, Vec3::new(3.0, 3.0, 3.0)).unwrap();
+
+    TransformHierarchy::set_global_scale(&mut world, b, Vec3::new(4.0, 4.0, 4.0)).unwrap();
+    let scale = TransformHierarchy::get_local_scale(&world, b).unwrap();
+    assert!(scale.abs_diff_eq(Vec3::new(3.0, 2.0, 4.0), 1e-5), "{:?}", scale);
+}
+
+/// Tree `a(b(d, e), c(f))`, returned in alphabetical order.
+fn tree(world: &mut World) -> [Entity; 6] {
+    let objects: Vec<Entity> = (0..6).map(|_| GameObject::create_empty(world)).collect();
+    let [a, b, c, d, e, f] = [objects[0], objects[1], objects[2], objects[3], objects[4], objects[5]];
+    for (child, parent) in [(b, a), (c, a), (d, b), (e, b), (f, c)].iter().copied() {
+        TransformHierarchy::set_parent(world, child, Some(parent)).unwrap();
+    }
+    [a, b, c, d, e, f]
+}
+
+#[test]
+fn descendants_and_ancestors_are_traversed_in_order() {
+    let mut world = world();
+    let [a, b, c, d, e, f] = tree(&mut world);
+
+    let depth_first: Vec<Entity> = TransformHierarchy::descendants_depth_first(&world, a)
+        .unwrap()
+        .collect();
+    assert_eq!(depth_first, vec![b, d, e, c, f]);
+    let breadth_first: Vec<Entity> = TransformHierarchy::descendants_breadth_first(&world, a)
+        .unwrap()
+        .collect();
+    assert_eq!(breadth_first, vec![b, c, d, e, f]);
+    let ancestors: Vec<Entity> = TransformHierarchy::ancestors(&world, e).unwrap().collect();
+    assert_eq!(ancestors, vec![b, a]);
+    assert_eq!(TransformHierarchy::ancestors(&world, a).unwrap().count(), 0);
+    assert_eq!(
+        TransformHierarchy::descendants_depth_first(&world, f).unwrap().count(),
+        0
+    );
+}
+
+#[test]
+fn children_are_reordered() {
+    let mut world = world();
+    let [a, b, c, d, e, _] = tree(&mut world);
+
+    TransformHierarchy::set_sibling_index(&mut world, c, 0).unwrap();
+    assert_eq!(children(&world, a), vec![c, b]);
+    assert_eq!(TransformHierarchy::get_sibling_index(&world, b), Ok(1));
+    TransformHierarchy::set_sibling_index(&mut world, c, 10).unwrap();
+    assert_eq!(children(&world, a), vec![b, c]);
+
+    // setting the same parent again keeps the order
+    TransformHierarchy::set_parent(&mut world, b, Some(a)).unwrap();
+    assert_eq!(children(&world, a), vec![b, c]);
+
+    // moved from other parent
+    TransformHierarchy::move_before(&mut world, d, c).unwrap();
+    assert_eq!(children(&world, a), vec![b, d, c]);
+    assert_eq!(children(&world, b), vec![e]);
+    TransformHierarchy::move_after(&mut world, b, c).unwrap();
+    assert_eq!(children(&world, a), vec![d, c, b]);
+    assert_eq!(TransformHierarchy::siblings(&world, c), Ok(vec![d, b]));
+}
+
+#[test]
+fn roots_keep_their_order() {
+    let mut world = world();
+    let x = GameObject::create_empty(&mut world);
+    let y = GameObject::create_empty(&mut world);
+    let z = GameObject::create_empty(&mut world);
+    assert_eq!(TransformHierarchy::roots(&world), vec![x, y, z]);
+
+    // storage order of `x` changes with its components, order of roots doesn't
+    world.add_component(x, 5u32);
+    assert_eq!(TransformHierarchy::roots(&world), vec![x, y, z]);
+
+    // unparented objects go to the end
+    TransformHierarchy::set_parent(&mut world, y, Some(x)).unwrap();
+    assert_eq!(TransformHierarchy::roots(&world), vec![x, z]);
+    TransformHierarchy::set_parent(&mut world, y, None).unwrap();
+    assert_eq!(TransformHierarchy::roots(&world), vec![x, z, y]);
+
+    GameObject::delete(&mut world, z);
+    GameObject::remove_marked(&mut world);
+    assert_eq!(TransformHierarchy::roots(&world), vec![x, y]);
+    assert_eq!(TransformHierarchy::siblings(&world, x), Ok(vec![y]));
+}
+
+#[test]
+fn roots_are_reordered() {
+    let mut world = world();
+    let x = GameObject::create_empty(&mut world);
+    let y = GameObject::create_empty(&mut world);
+    let z = GameObject::create_empty(&mut world);
+    let child = GameObject::create_empty(&mut world);
+    TransformHierarchy::set_parent(&mut world, child, Some(x)).unwrap();
+
+    TransformHierarchy::set_sibling_index(&mut world, z, 0).unwrap();
+    assert_eq!(TransformHierarchy::roots(&world), vec![z, x, y]);
+    TransformHierarchy::set_parent(&mut world, z, None).unwrap();
+    assert_eq!(TransformHierarchy::roots(&world), vec![z, x, y]);
+    assert_eq!(TransformHierarchy::get_sibling_index(&world, y), Ok(2));
+
+    // child of other object becomes root next to its new sibling
+    TransformHierarchy::move_after(&mut world, child, z).unwrap();
+    assert_eq!(TransformHierarchy::get_parent(&world, child), Ok(None));
+    assert_eq!(TransformHierarchy::roots(&world), vec![z, child, x, y]);
+    TransformHierarchy::move_before(&mut world, y, z).unwrap();
+    assert_eq!(TransformHierarchy::roots(&world), vec![y, z, child, x]);
+}
+
+#[test]
+fn reactivated_parent_restores_own_states_of_children() {
+    let mut world = world();
+    let [a, b, c, d, e, f] = tree(&mut world);
+    GameObject::set_active(&mut world, c, false).unwrap();
+    GameObject::set_active(&mut world, e, false).unwrap();
+
+    GameObject::set_active(&mut world, a, false).unwrap();
+    for id in [a, b, c, d, e, f].iter().copied() {
+        assert_eq!(GameObject::is_active_in_hierarchy(&world, id), Ok(false));
+    }
+    assert_eq!(GameObject::is_active_self(&world, b), Ok(true));
+
+    GameObject::set_active(&mut world, a, true).unwrap();
+    let active: Vec<bool> = [a, b, c, d, e, f]
+        .iter()
+        .map(|id| GameObject::is_active_in_hierarchy(&world, *id).unwrap())
+        .collect();
+    assert_eq!(active, vec![true, true, false, true, false, false]);
+    assert_eq!(GameObject::is_active_self(&world, c), Ok(false));
+    assert_eq!(GameObject::is_active_self(&world, f), Ok(true));
+
+    // moved out of inactive subtree
+    TransformHierarchy::set_parent(&mut world, f, Some(b)).unwrap();
+    assert_eq!(GameObject::is_active_in_hierarchy(&world, f), Ok(true));
+}
@@ -0,0 +1,34 @@
+//! Checks rotations made by `look_rotation`.
+
+use just_core::math::{look_rotation, Quat, Vec3};
+
+fn assert_close(a: Vec3, b: Vec3) {
+    assert!(a.abs_diff_eq(b, 1e-5), "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn forward_axis_points_at_direction() {
+    let direction = Vec3::new(1.0, 2.0, -3.0);
+    let rotation = look_rotation(direction, Vec3::Y);
+    assert_close(rotation * Vec3::Z, direction.normalize());
+    // up stays in the plane of direction and requested up
+    assert!((rotation * Vec3::Y).dot(Vec3::Y) > 0.0);
+    assert!((rotation * Vec3::X).dot(Vec3::Y).abs() < 1e-5);
+}
+
+#[test]
+fn up_parallel_to_direction_uses_other_axis() {
+    for direction in [Vec3::Y, -Vec3::Y, Vec3::new(0.0, 5.0, 0.0)].iter().copied() {
+        let rotation = look_rotation(direction, Vec3::Y);
+        assert!(rotation.is_finite(), "{:?}", rotation);
+        assert_close(rotation * Vec3::Z, direction.normalize());
+    }
+    let rotation = look_rotation(Vec3::X, Vec3::ZERO);
+    assert!(rotation.is_finite());
+    assert_close(rotation * Vec3::Z, Vec3::X);
+}
+
+#[test]
+fn zero_direction_gives_no_rotation() {
+    assert_eq!(look_rotation(Vec3::ZERO, Vec3::Y), Quat::IDENTITY);
+}
@@ -0,0 +1,91 @@
+//! Checks that `GameObjectIndex` follows names, tags and layers of game objects.
+
+use just_core::ecs::prelude::*;
+use just_core::game_object::{GameObject, DEFAULT_LAYER_MASK};
+use just_core::hierarchy::TransformHierarchy;
+
+fn world() -> World {
+    let mut world = World::default();
+    GameObject::initialize(&mut world);
+    world
+}
+
+fn named(world: &mut World, name: &str) -> Entity {
+    let id = GameObject::create_empty(world);
+    GameObject::set_name(world, id, name.to_owned()).unwrap();
+    id
+}
+
+#[test]
+fn renamed_objects_are_found_by_new_name() {
+    let mut world = world();
+    let a = named(&mut world, "enemy");
+    let b = named(&mut world, "enemy");
+    assert_eq!(GameObject::find_by_name(&world, "enemy"), vec![a, b]);
+
+    GameObject::set_name(&mut world, a, "player".to_owned()).unwrap();
+    assert_eq!(GameObject::find_by_name(&world, "enemy"), vec![b]);
+    assert_eq!(GameObject::find_by_name(&world, "player"), vec![a]);
+
+    // unnamed objects aren't indexed
+    GameObject::set_name(&mut world, b, String::new()).unwrap();
+    assert!(GameObject::find_by_name(&world, "enemy").is_empty());
+    assert!(GameObject::find_by_name(&world, "").is_empty());
+}
+
+#[test]
+fn removed_tags_are_unindexed() {
+    let mut world = world();
+    let a = GameObject::create_empty(&mut world);
+    let b = GameObject::create_empty(&mut world);
+    GameObject::add_tag(&mut world, a, "solid").unwrap();
+    GameObject::add_tag(&mut world, a, "solid").unwrap();
+    GameObject::add_tag(&mut world, b, "solid").unwrap();
+    assert_eq!(GameObject::find_by_tag(&world, "solid"), vec![a, b]);
+    assert_eq!(GameObject::get_tags(&world, a), Ok(vec!["solid".to_owned()]));
+
+    assert_eq!(GameObject::remove_tag(&mut world, a, "solid"), Ok(true));
+    assert_eq!(GameObject::remove_tag(&mut world, a, "solid"), Ok(false));
+    assert_eq!(GameObject::find_by_tag(&world, "solid"), vec![b]);
+    assert_eq!(GameObject::has_tag(&world, a, "solid"), Ok(false));
+    GameObject::remove_tag(&mut world, b, "solid").unwrap();
+    assert!(GameObject::find_by_tag(&world, "solid").is_empty());
+}
+
+#[test]
+fn layer_changes_move_objects_between_layers() {
+    let mut world = world();
+    let a = GameObject::create_empty(&mut world);
+    let b = GameObject::create_empty(&mut world);
+    assert_eq!(GameObject::get_layer_mask(&world, a), Ok(DEFAULT_LAYER_MASK));
+    assert_eq!(GameObject::find_in_layer(&world, 0), vec![a, b]);
+
+    GameObject::set_layer_mask(&mut world, a, 0b110).unwrap();
+    assert_eq!(GameObject::find_in_layer(&world, 0), vec![b]);
+    assert_eq!(GameObject::find_in_layer(&world, 1), vec![a]);
+    assert_eq!(GameObject::find_in_layer(&world, 2), vec![a]);
+    GameObject::set_layer_mask(&mut world, a, 1 << 31).unwrap();
+    assert!(GameObject::find_in_layer(&world, 1).is_empty());
+    assert_eq!(GameObject::find_in_layer(&world, 31), vec![a]);
+    assert!(GameObject::find_in_layer(&world, 32).is_empty());
+}
+
+#[test]
+fn removed_objects_are_unindexed() {
+    let mut world = world();
+    let parent = named(&mut world, "crate");
+    let child = named(&mut world, "crate");
+    let other = named(&mut world, "crate");
+    TransformHierarchy::set_parent(&mut world, child, Some(parent)).unwrap();
+    for id in [parent, child, other].iter().copied() {
+        GameObject::add_tag(&mut world, id, "loot").unwrap();
+    }
+    GameObject::set_layer_mask(&mut world, child, 0b10).unwrap();
+
+    // removes the child too
+    GameObject::remove_game_object(&mut world, parent).unwrap();
+    assert_eq!(GameObject::find_by_name(&world, "crate"), vec![other]);
+    assert_eq!(GameObject::find_by_tag(&world, "loot"), vec![other]);
+    assert_eq!(GameObject::find_in_layer(&world, 0), vec![other]);
+    assert!(GameObject::find_in_layer(&world, 1).is_empty());
+}
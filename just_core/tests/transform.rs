@@ -0,0 +1,120 @@
+//! Checks that `TransformSystem` gives the same global matrices as multiplying local ones up the hierarchy.
+
+use just_core::ecs::prelude::*;
+use just_core::game_object::GameObject;
+use just_core::hierarchy::TransformHierarchy;
+use just_core::math::{Matrix, Quat, Vec3};
+use just_core::transform::{GlobalTransform, Transform, TransformSystem};
+
+fn world() -> World {
+    let mut world = World::default();
+    GameObject::initialize(&mut world);
+    world
+}
+
+fn recursive_global(world: &World, id: Entity) -> Matrix {
+    let local = world.get_component::<Transform>(id).unwrap().local_matrix();
+    match TransformHierarchy::get_parent(world, id).unwrap() {
+        Some(parent) => recursive_global(world, parent) * local,
+        None => local,
+    }
+}
+
+fn assert_propagated(world: &World, objects: &[Entity]) {
+    for id in objects.iter().copied() {
+        let global = world.get_component::<GlobalTransform>(id).unwrap().0;
+        let expected = recursive_global(world, id);
+        assert!(
+            global.abs_diff_eq(expected, 1e-3),
+            "{:?}: {:?} != {:?}",
+            id,
+            global,
+            expected
+        );
+    }
+}
+
+/// Object with transform depending on `i`, so every level of hierarchy contributes differently.
+fn create(world: &mut World, i: usize) -> Entity {
+    let id = GameObject::create_empty(world);
+    let f = i as f32;
+    TransformHierarchy::set_local_position(world, id, Vec3::new(1.0, f * 0.1, -0.5)).unwrap();
+    TransformHierarchy::set_local_rotation(world, id, Quat::from_rotation_y(0.1 + f * 0.01)).unwrap();
+    // alternating, so that scale of deep chains stays close to 1 and errors of floats stay small
+    let scale = if i % 2 == 0 { 1.1 } else { 1.0 / 1.1 };
+    TransformHierarchy::set_local_scale(world, id, Vec3::new(scale, 1.0 / scale, 1.0)).unwrap();
+    id
+}
+
+fn deep_chain(world: &mut World, length: usize) -> Vec<Entity> {
+    let objects: Vec<Entity> = (0..length).map(|i| create(world, i)).collect();
+    for pair in objects.windows(2) {
+        TransformHierarchy::set_parent(world, pair[1], Some(pair[0])).unwrap();
+    }
+    objects
+}
+
+#[test]
+fn deep_chain_matches_recursive_result() {
+    let mut world = world();
+    let objects = deep_chain(&mut world, 200);
+    TransformSystem::update(&mut world);
+    assert_propagated(&world, &objects);
+
+    // changes in the middle and near the end, one of them below the other
+    TransformHierarchy::set_local_position(&mut world, objects[150], Vec3::new(0.0, 2.0, 0.0)).unwrap();
+    TransformHierarchy::set_local_scale(&mut world, objects[50], Vec3::new(2.0, 1.0, 1.0)).unwrap();
+    TransformSystem::update(&mut world);
+    assert_propagated(&world, &objects);
+}
+
+#[test]
+fn reparented_subtrees_match_recursive_result() {
+    let mut world = world();
+    let first = deep_chain(&mut world, 20);
+    let second = deep_chain(&mut world, 20);
+    let all: Vec<Entity> = first.iter().chain(second.iter()).copied().collect();
+    TransformSystem::update(&mut world);
+
+    TransformHierarchy::set_parent(&mut world, second[0], Some(first[10])).unwrap();
+    TransformSystem::update(&mut world);
+    assert_propagated(&world, &all);
+
+    // moved back to root, with world position kept
+    TransformHierarchy::set_parent_keep_world(&mut world, second[5], None).unwrap();
+    TransformHierarchy::set_parent(&mut world, first[15], Some(second[19])).unwrap();
+    TransformSystem::update(&mut world);
+    assert_propagated(&world, &all);
+}
+
+#[test]
+fn new_objects_are_propagated() {
+    let mut world = world();
+    let mut objects = deep_chain(&mut world, 5);
+    TransformSystem::update(&mut world);
+
+    // never modified after creation, its transform is dirty anyway
+    let child = GameObject::create_empty(&mut world);
+    TransformHierarchy::set_parent(&mut world, child, Some(objects[4])).unwrap();
+    objects.push(child);
+    TransformSystem::update(&mut world);
+    assert_propagated(&world, &objects);
+    assert_ne!(
+        world.get_component::<GlobalTransform>(child).unwrap().0,
+        Matrix::IDENTITY
+    );
+}
+
+#[test]
+fn removed_objects_are_skipped() {
+    let mut world = world();
+    let objects = deep_chain(&mut world, 5);
+    TransformSystem::update(&mut world);
+
+    TransformHierarchy::set_local_position(&mut world, objects[4], Vec3::ZERO).unwrap();
+    GameObject::delete(&mut world, objects[4]);
+    GameObject::remove_marked(&mut world);
+    TransformHierarchy::set_local_position(&mut world, objects[1], Vec3::ZERO).unwrap();
+    TransformSystem::update(&mut world);
+    assert_propagated(&world, &objects[..4]);
+}
@@ -125,7 +125,7 @@ impl KeyCode {
             KeyCode::Up => 53,
             KeyCode::Right => 54,
             KeyCode::Down => 55,
-            KeyCode::LControl => 5,
+            KeyCode::LControl => 56,
             KeyCode::LShift => 57,
             KeyCode::RControl => 58,
             KeyCode::RShift => 59,
@@ -200,6 +200,73 @@ impl KeyCode {
             _ => KeyCode::None,
         }
     }
+    /// Inverse of `from_string`.
+    pub fn to_str(self) -> &'static str {
+        match self {
+            KeyCode::Key0 => "0",
+            KeyCode::Key1 => "1",
+            KeyCode::Key2 => "2",
+            KeyCode::Key3 => "3",
+            KeyCode::Key4 => "4",
+            KeyCode::Key5 => "5",
+            KeyCode::Key6 => "6",
+            KeyCode::Key7 => "7",
+            KeyCode::Key8 => "8",
+            KeyCode::Key9 => "9",
+            KeyCode::B => "B",
+            KeyCode::A => "A",
+            KeyCode::C => "C",
+            KeyCode::D => "D",
+            KeyCode::E => "E",
+            KeyCode::F => "F",
+            KeyCode::G => "G",
+            KeyCode::H => "H",
+            KeyCode::I => "I",
+            KeyCode::J => "J",
+            KeyCode::K => "K",
+            KeyCode::L => "L",
+            KeyCode::M => "M",
+            KeyCode::N => "N",
+            KeyCode::O => "O",
+            KeyCode::P => "P",
+            KeyCode::Q => "Q",
+            KeyCode::R => "R",
+            KeyCode::S => "S",
+            KeyCode::T => "T",
+            KeyCode::U => "U",
+            KeyCode::V => "V",
+            KeyCode::W => "W",
+            KeyCode::X => "X",
+            KeyCode::Y => "Y",
+            KeyCode::Z => "Z",
+            KeyCode::Escape => "Escape",
+            KeyCode::F1 => "F1",
+            KeyCode::F2 => "F2",
+            KeyCode::F3 => "F3",
+            KeyCode::F4 => "F4",
+            KeyCode::F5 => "F5",
+            KeyCode::F6 => "F6",
+            KeyCode::F7 => "F7",
+            KeyCode::F8 => "F8",
+            KeyCode::F9 => "F9",
+            KeyCode::F10 => "F10",
+            KeyCode::F11 => "F11",
+            KeyCode::F12 => "F12",
+            KeyCode::F13 => "F13",
+            KeyCode::F14 => "F14",
+            KeyCode::F15 => "F15",
+            KeyCode::Left => "Left",
+            KeyCode::Up => "Up",
+            KeyCode::Right => "Right",
+            KeyCode::Down => "Down",
+            KeyCode::LControl => "LControl",
+            KeyCode::LShift => "LShift",
+            KeyCode::RControl => "RControl",
+            KeyCode::RShift => "RShift",
+            KeyCode::Space => "Space",
+            KeyCode::None => "None",
+        }
+    }
     pub fn from_kc_enum(value: KC) -> KeyCode {
         use KC::*;
         match value {
@@ -0,0 +1,155 @@
+use std::collections::VecDeque;
+
+use egui::{Color32, ComboBox, ScrollArea};
+
+const MAX_ENTRIES: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+    Info,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConsoleEntry {
+    pub level: LogLevel,
+    /// What produced the entry, e.g. script controller. Entries can be filtered by it.
+    pub source: Option<String>,
+    pub message: String,
+    /// `file:line:column`, as much of it as is known.
+    pub location: Option<String>,
+    pub stack: Vec<String>,
+    /// How many times the entry was logged.
+    pub count: u32,
+}
+
+impl ConsoleEntry {
+    pub fn info(source: Option<String>, message: String) -> Self {
+        ConsoleEntry {
+            level: LogLevel::Info,
+            source,
+            message,
+            location: None,
+            stack: vec![],
+            count: 1,
+        }
+    }
+
+    pub fn error(source: Option<String>, message: String) -> Self {
+        ConsoleEntry {
+            level: LogLevel::Error,
+            ..Self::info(source, message)
+        }
+    }
+
+    fn same_as(&self, other: &ConsoleEntry) -> bool {
+        self.level == other.level
+            && self.source == other.source
+            && self.message == other.message
+            && self.location == other.location
+    }
+}
+
+/// Log shown in console window of `Ui`. Repeated errors (e.g. thrown every frame) are collapsed
+/// into single entry with repeat count, repeated messages only when they come one after another.
+#[derive(Default)]
+pub struct Console {
+    entries: VecDeque<ConsoleEntry>,
+    source_filter: Option<String>,
+    errors_only: bool,
+}
+
+impl Console {
+    pub fn log(&mut self, entry: ConsoleEntry) {
+        let repeated = match entry.level {
+            LogLevel::Error => self.entries.iter_mut().rev().find(|e| e.same_as(&entry)),
+            LogLevel::Info => self.entries.back_mut().filter(|e| e.same_as(&entry)),
+        };
+        if let Some(repeated) = repeated {
+            repeated.count += entry.count;
+            return;
+        }
+        if self.entries.len() == MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &ConsoleEntry> {
+        self.entries.iter()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        egui::Window::new("Console")
+            .default_size([600.0, 250.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let mut sources: Vec<&String> = self.entries.iter().filter_map(|e| e.source.as_ref()).collect();
+                    sources.sort();
+                    sources.dedup();
+                    let selected = self.source_filter.clone().unwrap_or_else(|| "all".to_owned());
+                    ComboBox::from_label("source")
+                        .selected_text(selected)
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.source_filter, None, "all");
+                            for source in sources {
+                                ui.selectable_value(&mut self.source_filter, Some(source.clone()), source.as_str());
+                            }
+                        });
+                    ui.checkbox(&mut self.errors_only, "errors only");
+                    if ui.button("clear").clicked() {
+                        self.entries.clear();
+                    }
+                });
+                ui.separator();
+
+                let source_filter = &self.source_filter;
+                let errors_only = self.errors_only;
+                let visible = self.entries.iter().enumerate().filter(|(_, entry)| {
+                    (!errors_only || entry.level == LogLevel::Error)
+                        && (source_filter.is_none() || entry.source == *source_filter)
+                });
+                ScrollArea::vertical().stick_to_bottom(true).show(ui, |ui| {
+                    for (index, entry) in visible {
+                        Self::show_entry(ui, index, entry);
+                    }
+                });
+            });
+    }
+
+    fn show_entry(ui: &mut egui::Ui, index: usize, entry: &ConsoleEntry) {
+        let mut text = String::new();
+        if entry.count > 1 {
+            text.push_str(&format!("({}) ", entry.count));
+        }
+        if let Some(source) = &entry.source {
+            text.push_str(&format!("[{}] ", source));
+        }
+        text.push_str(&entry.message);
+        if let Some(location) = &entry.location {
+            text.push_str(&format!(" at {}", location));
+        }
+        match entry.level {
+            LogLevel::Info => {
+                ui.label(text);
+            }
+            LogLevel::Error if entry.stack.is_empty() => {
+                ui.colored_label(Color32::LIGHT_RED, text);
+            }
+            LogLevel::Error => {
+                // text changes with repeat count, so it can't identify the header
+                egui::CollapsingHeader::new(egui::RichText::new(text).color(Color32::LIGHT_RED))
+                    .id_source(index)
+                    .show(ui, |ui| {
+                        for frame in entry.stack.iter() {
+                            ui.monospace(frame);
+                        }
+                    });
+            }
+        }
+    }
+}
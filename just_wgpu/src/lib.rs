@@ -1,4 +1,5 @@
 mod camera;
+mod console;
 mod model;
 mod obj_loader;
 mod postprocessing;
@@ -17,17 +18,19 @@ use std::ops::{Deref, DerefMut, Range};
 
 pub use camera::CameraData;
 use camera::CameraUniform;
+pub use console::{Console, ConsoleEntry, LogLevel};
 use egui::RawInput;
 use egui_wgpu::renderer::ScreenDescriptor;
-use just_core::hierarchy::TransformHierarchy;
+use just_core::game_object::GameObject;
+use just_core::transform::GlobalTransform;
 use model::{MeshData, MeshVertex};
 pub use screen_data::ScreenData;
 use viewport::ViewportData;
 
 use just_assets::{AssetManager, AssetStorage};
+use just_core::commands::Commands;
 use just_core::ecs::prelude::*;
 use just_core::ecs::world::World;
-use just_core::RenderableCreationQueue;
 use just_input::{InputChannel, KeyboardState, MouseState};
 use obj_loader::load_obj_model;
 use postprocessing::PostprocessingPass;
@@ -59,6 +62,36 @@ pub struct RenderingSystem {}
 pub struct Renderable {
     mesh: Mesh,
     texture: Texture,
+    // asset names are kept so that scenes can be written back
+    mesh_name: String,
+    texture_name: String,
+}
+
+impl Renderable {
+    pub fn mesh_name(&self) -> &str {
+        &self.mesh_name
+    }
+
+    pub fn texture_name(&self) -> &str {
+        &self.texture_name
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenderableError {
+    NoRenderable,
+    MeshNotLoaded(String),
+    TextureNotLoaded(String),
+}
+
+impl std::fmt::Display for RenderableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderableError::NoRenderable => write!(f, "object has no renderable"),
+            RenderableError::MeshNotLoaded(name) => write!(f, "mesh '{}' is not loaded", name),
+            RenderableError::TextureNotLoaded(name) => write!(f, "texture '{}' is not loaded", name),
+        }
+    }
 }
 
 struct EguiSystem {
@@ -318,7 +351,6 @@ impl RenderingSystem {
         let manager = pollster::block_on(Self::initialize_wgpu(event_loop, world));
 
         world.resources.insert::<RenderingManager>(manager);
-        world.resources.insert::<RenderableCreationQueue>(Default::default());
     }
 
     pub fn maintain(_world: &mut World) {}
@@ -332,7 +364,6 @@ impl RenderingSystem {
             mut ui,
             keyboard,
             screen_data,
-            mut creation_queue,
         ) = <(
             Write<RenderingManager>,
             Write<EguiSystem>,
@@ -342,7 +373,6 @@ impl RenderingSystem {
             Write<Ui>,
             Read<KeyboardState>,
             Read<ScreenData>,
-            Write<RenderableCreationQueue>,
         )>::fetch(&mut world.resources);
 
         // loading requested assets
@@ -354,13 +384,6 @@ impl RenderingSystem {
             (load_obj_model(&mut manager, data, name), false)
         });
 
-        //creating renderables requested by game logic
-        let to_create = std::mem::take(&mut creation_queue.queue);
-
-        for (id, mesh, texture) in to_create.into_iter() {
-            Self::add_renderable(world, id, &mesh, &texture);
-        }
-
         // update camera data
         manager.camera_uniform.update_view_projection(&screen_data.camera);
         manager.queue.write_buffer(
@@ -409,9 +432,12 @@ impl RenderingSystem {
             render_pass.set_pipeline(&manager.standard_pass.render_pipeline);
             render_pass.set_bind_group(1, &manager.camera_uniform.bind_group, &[]);
 
-            let query = <Read<Renderable>>::query();
-            for (id, renderable) in query.iter_entities_immutable(world) {
-                let global_matrix = TransformHierarchy::get_global_matrix(world, id);
+            let query = <(Read<Renderable>, Read<GlobalTransform>, Read<GameObject>)>::query();
+            for (renderable, global_matrix, object) in query.iter_immutable(world) {
+                if !object.active_in_hierarchy() {
+                    continue;
+                }
+                let global_matrix = global_matrix.0;
                 let mesh = renderable.mesh;
                 let tex = renderable.texture;
                 let tex_bind_group = manager.texture_bindings.get(&tex).unwrap();
@@ -486,14 +512,14 @@ impl RenderingSystem {
 
     /// Only to be used in scene deserialization
     /// Does not check if renderable already exists
-    pub fn add_renderable(world: &mut World, id: Entity, mesh: &str, texture: &str) {
+    pub fn add_renderable(world: &mut World, id: Entity, mesh_name: &str, texture_name: &str) {
         let mesh_storage = world.resources.get::<AssetStorage<Mesh>>().unwrap();
         let texture_storage = world.resources.get::<AssetStorage<Texture>>().unwrap();
 
-        let mesh_handle = mesh_storage.get_handle(mesh).unwrap();
+        let mesh_handle = mesh_storage.get_handle(mesh_name).unwrap();
         let mesh = *mesh_storage.get_value(&mesh_handle).unwrap();
 
-        let texture_handle = texture_storage.get_handle(texture).unwrap();
+        let texture_handle = texture_storage.get_handle(texture_name).unwrap();
         let texture = *texture_storage.get_value(&texture_handle).unwrap();
         drop(mesh_storage);
         drop(texture_storage);
@@ -503,12 +529,81 @@ impl RenderingSystem {
             Renderable {
                 mesh: mesh,
                 texture: texture,
+                mesh_name: mesh_name.to_owned(),
+                texture_name: texture_name.to_owned(),
             },
         );
     }
 }
 
 impl RenderingSystem {
+    /// Swaps mesh of object's renderable, including renderables recorded in `Commands` and not created yet.
+    pub fn set_mesh(world: &mut World, id: Entity, mesh_name: &str) -> Result<(), RenderableError> {
+        let mesh = {
+            let storage = world.resources.get::<AssetStorage<Mesh>>().unwrap();
+            storage
+                .get_handle(mesh_name)
+                .and_then(|handle| storage.get_value(&handle).copied())
+                .ok_or_else(|| RenderableError::MeshNotLoaded(mesh_name.to_owned()))?
+        };
+        if let Some(mut renderable) = world.get_component_mut::<Renderable>(id) {
+            renderable.mesh = mesh;
+            renderable.mesh_name = mesh_name.to_owned();
+            return Ok(());
+        }
+        let mut commands = world.resources.get_mut::<Commands>().unwrap();
+        match commands.queued_renderable_mut(id) {
+            Some((queued_mesh, _)) => {
+                *queued_mesh = mesh_name.to_owned();
+                Ok(())
+            }
+            None => Err(RenderableError::NoRenderable),
+        }
+    }
+
+    /// Swaps texture of object's renderable, including renderables recorded in `Commands` and not created yet.
+    pub fn set_texture(world: &mut World, id: Entity, texture_name: &str) -> Result<(), RenderableError> {
+        let texture = {
+            let storage = world.resources.get::<AssetStorage<Texture>>().unwrap();
+            storage
+                .get_handle(texture_name)
+                .and_then(|handle| storage.get_value(&handle).copied())
+                .ok_or_else(|| RenderableError::TextureNotLoaded(texture_name.to_owned()))?
+        };
+        if let Some(mut renderable) = world.get_component_mut::<Renderable>(id) {
+            renderable.texture = texture;
+            renderable.texture_name = texture_name.to_owned();
+            return Ok(());
+        }
+        let mut commands = world.resources.get_mut::<Commands>().unwrap();
+        match commands.queued_renderable_mut(id) {
+            Some((_, queued_texture)) => {
+                *queued_texture = texture_name.to_owned();
+                Ok(())
+            }
+            None => Err(RenderableError::NoRenderable),
+        }
+    }
+
+    /// Gives `target` the same mesh and texture as `source`, registered as cloner of `Commands`.
+    /// Renderable recorded in `Commands` and not created yet is recorded for `target` too.
+    pub fn clone_renderable(world: &mut World, source: Entity, target: Entity) {
+        let names = world
+            .get_component::<Renderable>(source)
+            .map(|renderable| (renderable.mesh_name.clone(), renderable.texture_name.clone()));
+        if let Some((mesh_name, texture_name)) = names {
+            Self::add_renderable(world, target, &mesh_name, &texture_name);
+            return;
+        }
+        let mut commands = world.resources.get_mut::<Commands>().unwrap();
+        let queued = commands
+            .queued_renderable(source)
+            .map(|(mesh_name, texture_name)| (mesh_name.to_owned(), texture_name.to_owned()));
+        if let Some((mesh_name, texture_name)) = queued {
+            commands.add_renderable(target, &mesh_name, &texture_name);
+        }
+    }
+
     fn load_png_texture(renderer: &mut RenderingManager, data: &[u8], name: &str) -> Texture {
         let image_data = TextureData::from_bytes(&renderer.device, &renderer.queue, data, name).unwrap();
 
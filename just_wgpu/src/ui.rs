@@ -4,6 +4,8 @@ use egui::{Event, FullOutput, Modifiers, Pos2, RawInput};
 use just_core::ecs::prelude::*;
 use just_input::{InputChannel, InputEvent, InputEvents, InputReader, KeyboardState, MouseState};
 
+use crate::Console;
+
 pub struct UiState {
     message: String,
 }
@@ -32,6 +34,7 @@ impl Ui {
             let mut channel = world.resources.get_mut::<InputChannel>().unwrap();
             channel.register_reader()
         };
+        world.resources.insert(Console::default());
         world.resources.insert(UiState {
             message: "default".to_owned(),
         });
@@ -53,9 +56,10 @@ impl Ui {
     }
 
     pub fn update(world: &mut World) -> FullOutput {
-        let (mut ui, mut state, channel, keyboard, mouse) = <(
+        let (mut ui, mut state, mut console, channel, keyboard, mouse) = <(
             Write<Ui>,
             Write<UiState>,
+            Write<Console>,
             Read<InputChannel>,
             Read<KeyboardState>,
             Read<MouseState>,
@@ -88,6 +92,7 @@ impl Ui {
                     println!("clicked button");
                 }
             });
+            console.show(ctx);
         })
     }
 }